@@ -1,7 +1,7 @@
 #![feature(generic_const_exprs)]
 
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
-use go_game::{BoardSize19x19, Game, Move, Pos, SgfGame};
+use go_game::{Analysis, Board, BoardSize19x19, Game, GroupInfo, NumStones, Pos, SgfGame};
 
 const GAME1_SGF: &str = include_str!("../tests/sixteen_soldiers.sgf");
 const GAME2_SGF: &str = include_str!("../tests/3bw-lee-changseok-park-jungwhan.sgf");
@@ -10,20 +10,26 @@ const GAME3_SGF: &str = include_str!("../tests/3bw-gokifu-han-chongjin-le-changh
 fn simulate_game(sgf_game: &SgfGame) {
     let mut game = Game::<BoardSize19x19>::new();
     for game_move in &sgf_game.moves {
-        match game_move {
-            Move::Pass => {
-                game.pass_turn();
-            }
-            Move::Place { x, y } => {
-                game.place_stone(Pos::from_xy(usize::from(*x), usize::from(*y)))
-                    .unwrap();
-            }
-        }
+        game.play_move(game_move).unwrap();
     }
     // TODO Access outcome
     black_box(game);
 }
 
+/// Liberties of the group at `pos` by analyzing the whole board, for comparison with
+/// [Board::liberties_of_group_at]
+fn liberties_with_analysis(
+    board: &Board<BoardSize19x19>,
+    pos: Pos<BoardSize19x19>,
+) -> Option<NumStones<BoardSize19x19>> {
+    let analysis = Analysis::analyze(board);
+    let group = analysis.group_at(pos);
+    match analysis.group_info(group) {
+        GroupInfo::PlayerGroup { liberties, .. } => Some(*liberties),
+        GroupInfo::EmptyStonesGroup => None,
+    }
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     let game1 = go_game::parse_sgf(GAME1_SGF).unwrap();
     let game2 = go_game::parse_sgf(GAME2_SGF).unwrap();
@@ -32,6 +38,35 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("game1", |b| b.iter(|| simulate_game(&game1)));
     c.bench_function("game2", |b| b.iter(|| simulate_game(&game2)));
     c.bench_function("game3", |b| b.iter(|| simulate_game(&game3)));
+
+    // Counting the final positions
+    for (name, sgf_game) in [
+        ("score_game1", &game1),
+        ("score_game2", &game2),
+        ("score_game3", &game3),
+    ] {
+        let game = sgf_game
+            .game_position_after_num_moves(sgf_game.moves.len())
+            .unwrap();
+        c.bench_function(name, |b| b.iter(|| black_box(&game).score(black_box(&[]))));
+    }
+
+    // Single group queries on a crowded endgame position
+    let game = game2
+        .game_position_after_num_moves(game2.moves.len())
+        .unwrap();
+    let board = *game.board();
+    let pos = game.last_move().unwrap();
+    assert_eq!(
+        board.liberties_of_group_at(pos),
+        liberties_with_analysis(&board, pos)
+    );
+    c.bench_function("liberties_flood_fill", |b| {
+        b.iter(|| black_box(&board).liberties_of_group_at(black_box(pos)))
+    });
+    c.bench_function("liberties_analysis", |b| {
+        b.iter(|| liberties_with_analysis(black_box(&board), black_box(pos)))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,7 +1,9 @@
+use bitvec::bitvec;
 use derive_where::derive_where;
+use enum_map::{EnumMap, enum_map};
 
 use crate::{
-    Board, BoardSize, NumStones, Player,
+    Board, BoardSize, NumStones, Player, StoneStyle,
     board::Pos,
     group_stones::{GroupId, GroupedStones, group_connected_stones},
     utils::SmallSet,
@@ -16,14 +18,10 @@ pub enum GroupInfo<BS: BoardSize> {
         liberties: NumStones<BS>,
     },
     EmptyStonesGroup,
-    // TODO Unknown can only happen while building the analysis. Is there a better way to handle this?
-    Unknown {
-        liberties: NumStones<BS>,
-    },
 }
 
 /// Analyses a board position, determining groups, liberties, and other properties.
-#[derive_where(Debug, PartialEq, Eq)]
+#[derive_where(Debug, Clone, PartialEq, Eq)]
 pub struct Analysis<BS: BoardSize>
 where
     [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
@@ -43,22 +41,37 @@ where
 {
     pub fn analyze(board: &Board<BS>) -> Self {
         let pos_to_group = group_connected_stones(board);
-        let group_info = Self::_liberties_and_owners_of_groups(board, &pos_to_group);
+        let mut group_info = Vec::new();
+        Self::_liberties_and_owners_of_groups(board, &pos_to_group, &mut group_info);
 
         Self {
-            pos_to_group: pos_to_group.into(),
+            pos_to_group: pos_to_group.into_pos_to_group(),
             group_info,
         }
     }
 
+    /// Analyzes `board` from scratch like [Self::analyze], but reuses the memory of this analysis,
+    /// so replaying a game doesn't need to allocate the group info for every move.
+    pub fn reanalyze(&mut self, board: &Board<BS>) {
+        let pos_to_group = group_connected_stones(board);
+        Self::_liberties_and_owners_of_groups(board, &pos_to_group, &mut self.group_info);
+        self.pos_to_group = pos_to_group.into_pos_to_group();
+    }
+
     /// Remove a stone without splitting the group it belongs to.
     ///
     /// WARNING: This is only valid to call if the group is fully enclosed, i.e. doesn't connect to any other empty groups.
+    /// Use [Self::is_enclosed] to check.
     pub fn capture_group(
         &mut self,
         group_to_capture: GroupId<BS>,
         mut on_remove: impl FnMut(Pos<BS>),
     ) {
+        debug_assert!(
+            self.is_enclosed(group_to_capture),
+            "Tried to capture a group that still has liberties"
+        );
+        log::trace!("Capturing group {}", group_to_capture.into_usize());
         self.group_info[group_to_capture.into_usize()] = GroupInfo::EmptyStonesGroup;
 
         // TODO Would it be overall faster to keep a map of groups to positions around instead of iterating over all positions?
@@ -70,7 +83,6 @@ where
                 // And give each neighboring group a liberty
                 for group in self.find_neighboring_groups(pos).iter() {
                     match &mut self.group_info[group.into_usize()] {
-                        GroupInfo::Unknown { .. } => unreachable!(),
                         GroupInfo::PlayerGroup { liberties, .. } => *liberties += NumStones::ONE,
                         GroupInfo::EmptyStonesGroup => {
                             panic!(
@@ -83,6 +95,84 @@ where
         }
     }
 
+    /// Places a stone for `player` at the empty `pos` and updates the analysis for just that change
+    /// instead of analyzing the whole board again. Nothing is captured, so groups may be left without
+    /// liberties. This analysis must belong to `board`, and afterwards it equals [Self::analyze] of
+    /// the new board.
+    pub fn add_stone(&mut self, board: &mut Board<BS>, pos: Pos<BS>, player: Player) {
+        assert!(
+            board[pos].is_none(),
+            "Can only add a stone to an empty cell"
+        );
+        board.set(pos, Some(player));
+        self._update_around(board, pos);
+    }
+
+    /// Removes the stone at `pos`, e.g. when it's marked as dead, and updates the analysis for just that
+    /// change like [Self::add_stone]. This analysis must belong to `board`.
+    pub fn remove_stone(&mut self, board: &mut Board<BS>, pos: Pos<BS>) {
+        assert!(
+            board[pos].is_some(),
+            "Can only remove a stone from an occupied cell"
+        );
+        board.set(pos, None);
+        self._update_around(board, pos);
+    }
+
+    /// Only the groups containing `pos` or one of its neighbors can change when the cell at `pos` changed,
+    /// so they are flood filled again on the new `board`. All other groups keep their cells and liberties.
+    fn _update_around(&mut self, board: &Board<BS>, pos: Pos<BS>) {
+        let mut new_group_of = vec![None; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
+        let mut new_groups = Vec::new();
+        for start in std::iter::once(pos).chain(pos.neighbors()) {
+            if new_group_of[start.index()].is_some() {
+                continue;
+            }
+            let owner = board[start];
+            for cell in board.flood_fill(start, |cell| cell == owner) {
+                new_group_of[cell.index()] = Some(new_groups.len());
+            }
+            new_groups.push(match owner {
+                Some(owner) => GroupInfo::PlayerGroup {
+                    owner,
+                    liberties: board
+                        .liberties_of_group_at(start)
+                        .expect("Start is a stone"),
+                },
+                None => GroupInfo::EmptyStonesGroup,
+            });
+        }
+
+        // Renumber the groups in the order they first appear, which is the numbering of [Self::analyze]
+        let mut old_to_id = vec![None; self.group_info.len()];
+        let mut new_to_id = vec![None; new_groups.len()];
+        let mut group_info = Vec::new();
+        for cell in Pos::all_positions() {
+            let (id, info) = match new_group_of[cell.index()] {
+                Some(new_group) => (&mut new_to_id[new_group], new_groups[new_group]),
+                None => {
+                    let old_group = self.group_at(cell).into_usize();
+                    (&mut old_to_id[old_group], self.group_info[old_group])
+                }
+            };
+            let id = *id.get_or_insert_with(|| {
+                group_info.push(info);
+                GroupId::from_usize(group_info.len() - 1)
+            });
+            self.pos_to_group[cell.index()] = id;
+        }
+        self.group_info = group_info;
+    }
+
+    /// A group is fully enclosed if it is a group of stones without any liberties left.
+    /// Such a group can be captured with [Self::capture_group].
+    pub fn is_enclosed(&self, group: GroupId<BS>) -> bool {
+        match self.group_info[group.into_usize()] {
+            GroupInfo::PlayerGroup { liberties, .. } => liberties == NumStones::ZERO,
+            GroupInfo::EmptyStonesGroup => false,
+        }
+    }
+
     fn find_neighboring_groups(&self, pos: Pos<BS>) -> SmallSet<[GroupId<BS>; 4]> {
         let self_group = self.group_at(pos);
         let mut neighboring_groups = SmallSet::<[GroupId<BS>; 4]>::new();
@@ -102,86 +192,206 @@ where
         neighboring_groups
     }
 
+    /// All groups touching `group`, including empty regions.
+    pub fn adjacent_groups(&self, group: GroupId<BS>) -> SmallSet<[GroupId<BS>; 8]> {
+        let mut adjacent_groups = SmallSet::new();
+        for pos in Pos::all_positions() {
+            if self.group_at(pos) == group {
+                for neighbor in self.find_neighboring_groups(pos).iter() {
+                    adjacent_groups.insert(*neighbor);
+                }
+            }
+        }
+        adjacent_groups
+    }
+
+    /// For a capturing race (semeai) between two groups of stones: how many liberties `group_a` has
+    /// more than `group_b`. Positive if `group_a` is ahead, negative if it is behind. `None` if one of
+    /// the groups is an empty region, since only groups of stones can race for liberties.
+    pub fn liberty_race(&self, group_a: GroupId<BS>, group_b: GroupId<BS>) -> Option<i32> {
+        let liberties = |group| match self.group_info(group) {
+            GroupInfo::PlayerGroup { liberties, .. } => Some(liberties.into_usize() as i32),
+            GroupInfo::EmptyStonesGroup => None,
+        };
+        Some(liberties(group_a)? - liberties(group_b)?)
+    }
+
+    /// How many liberties the group of a stone that `player` places at `pos` would have, after merging with
+    /// the neighboring groups of `player` and capturing opponent groups. `None` if `pos` is occupied or the
+    /// move would be suicide. This analysis must belong to `board`, and ko isn't considered.
+    pub fn liberties_after_move(
+        &self,
+        board: &Board<BS>,
+        pos: Pos<BS>,
+        player: Player,
+    ) -> Option<NumStones<BS>> {
+        if board[pos].is_some() {
+            return None;
+        }
+        let mut merged_groups = SmallSet::<[GroupId<BS>; 4]>::new();
+        let mut captured_groups = SmallSet::<[GroupId<BS>; 4]>::new();
+        for neighbor in pos.neighbors() {
+            let group = self.group_at(neighbor);
+            match self.group_info(group) {
+                GroupInfo::PlayerGroup { owner, .. } if *owner == player => {
+                    merged_groups.insert(group);
+                }
+                GroupInfo::PlayerGroup { liberties, .. } if *liberties == NumStones::ONE => {
+                    captured_groups.insert(group);
+                }
+                _ => {}
+            }
+        }
+
+        // Any empty or captured cell next to the new stone or one of the merged groups is a liberty
+        let mut is_liberty = bitvec![0; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
+        let stones = Pos::all_positions()
+            .filter(|stone| merged_groups.contains(&self.group_at(*stone)))
+            .chain([pos]);
+        for stone in stones {
+            for neighbor in stone.neighbors() {
+                let becomes_empty =
+                    board[neighbor].is_none() || captured_groups.contains(&self.group_at(neighbor));
+                if neighbor != pos && becomes_empty {
+                    is_liberty.set(neighbor.index(), true);
+                }
+            }
+        }
+        match is_liberty.count_ones() {
+            0 => None,
+            liberties => Some(NumStones::from_usize(liberties)),
+        }
+    }
+
     pub fn group_at(&self, pos: Pos<BS>) -> GroupId<BS> {
         self.pos_to_group[pos.index()]
     }
 
+    /// Whether `a` and `b` both hold stones of the same group. Empty points are never connected,
+    /// even if they're in the same empty region.
+    pub fn are_connected(&self, a: Pos<BS>, b: Pos<BS>) -> bool {
+        let group = self.group_at(a);
+        group == self.group_at(b) && matches!(self.group_info(group), GroupInfo::PlayerGroup { .. })
+    }
+
+    /// Owner and liberties of `group`. Panics if `group` isn't a group of this analysis.
+    pub fn group_info(&self, group: GroupId<BS>) -> &GroupInfo<BS> {
+        assert!(
+            group.into_usize() < self.group_info.len(),
+            "Group {} doesn't exist, there are only {} groups",
+            group.into_usize(),
+            self.group_info.len()
+        );
+        &self.group_info[group.into_usize()]
+    }
+
     fn _liberties_and_owners_of_groups(
         board: &Board<BS>,
         pos_to_group: &GroupedStones<BS>,
-    ) -> Vec<GroupInfo<BS>> {
-        let mut liberties_and_owners = vec![
-            GroupInfo::Unknown {
-                liberties: NumStones::ZERO
-            };
-            pos_to_group.num_groups().into_usize()
-        ];
+        liberties_and_owners: &mut Vec<GroupInfo<BS>>,
+    ) {
+        liberties_and_owners.clear();
+        liberties_and_owners.extend((0..pos_to_group.num_groups().into_usize()).map(|group| {
+            match pos_to_group.owner(GroupId::from_usize(group)) {
+                Some(owner) => GroupInfo::PlayerGroup {
+                    owner,
+                    liberties: NumStones::ZERO,
+                },
+                None => GroupInfo::EmptyStonesGroup,
+            }
+        }));
 
+        // Each empty cell is a liberty of every neighboring group of stones.
+        // But we need to make sure we only add it once if two neighboring fields are from the same group.
         for pos in Pos::all_positions() {
-            if let Some(owner) = board[pos] {
-                // It's a filled cell. Remember the owner of this group
-                let group = pos_to_group.group_at(pos).into_usize();
-                match liberties_and_owners[group] {
-                    GroupInfo::EmptyStonesGroup => {
-                        liberties_and_owners[group] = GroupInfo::PlayerGroup {
-                            owner,
-                            liberties: NumStones::ZERO,
-                        };
-                    }
-                    GroupInfo::Unknown { liberties } => {
-                        liberties_and_owners[group] = GroupInfo::PlayerGroup { owner, liberties };
-                    }
-                    GroupInfo::PlayerGroup {
-                        owner: actual_owner,
-                        liberties: _liberties,
-                    } => {
-                        assert_eq!(owner, actual_owner);
-                    }
-                }
-            } else {
-                let group = pos_to_group.group_at(pos).into_usize();
-                liberties_and_owners[group] = GroupInfo::EmptyStonesGroup;
-
-                // It's an empty cell. Any neighboring group that is occupied will get a liberty added.
-                // But we need to make sure we only add it once if two neighboring fields are from the same group.
-                // This code also adds liberties to the group representing the empty cells but that doesn't really matter.
-                let mut groups_to_add_liberty_to: SmallSet<[GroupId<BS>; 5]> = SmallSet::new();
-                groups_to_add_liberty_to.insert(pos_to_group.group_at(pos));
-                if let Some(left) = pos.left() {
-                    groups_to_add_liberty_to.insert(pos_to_group.group_at(left));
-                }
-                if let Some(top) = pos.up() {
-                    groups_to_add_liberty_to.insert(pos_to_group.group_at(top));
-                }
-                if let Some(right) = pos.right() {
-                    groups_to_add_liberty_to.insert(pos_to_group.group_at(right));
-                }
-                if let Some(down) = pos.down() {
-                    groups_to_add_liberty_to.insert(pos_to_group.group_at(down));
-                }
-                for group_index in groups_to_add_liberty_to.iter() {
-                    match &mut liberties_and_owners[group_index.into_usize()] {
-                        GroupInfo::Unknown { liberties } => *liberties += NumStones::ONE,
-                        GroupInfo::PlayerGroup {
-                            owner: _owner,
-                            liberties,
-                        } => *liberties += NumStones::ONE,
-                        GroupInfo::EmptyStonesGroup => {
-                            // ignore, we don't care about the number of liberties of empty groups
-                        }
-                    }
-                }
+            if board[pos].is_some() {
+                continue;
+            }
+            let groups_to_add_liberty_to: SmallSet<[GroupId<BS>; 4]> = pos
+                .neighbors()
+                .filter(|neighbor| board[*neighbor].is_some())
+                .map(|neighbor| pos_to_group.group_at(neighbor))
+                .collect();
+            for group_index in groups_to_add_liberty_to.iter() {
+                let GroupInfo::PlayerGroup { liberties, .. } =
+                    &mut liberties_and_owners[group_index.into_usize()]
+                else {
+                    unreachable!("Groups of stones never contain empty cells");
+                };
+                *liberties += NumStones::ONE;
             }
         }
+    }
 
-        debug_assert!(
-            !liberties_and_owners
-                .iter()
-                .any(|info| matches!(info, GroupInfo::Unknown { .. })),
-            "Analysis left an Unknown group behind"
-        );
+    /// Clears the stones of the `dead` groups from `board`, e.g. before counting at the end of the game.
+    /// Returns how many stones of each player were removed. Empty groups in `dead` are ignored.
+    ///
+    /// This analysis must belong to `board` and isn't updated, so re-analyze the board afterwards.
+    pub fn remove_dead_groups(
+        &self,
+        board: &mut Board<BS>,
+        dead: &[GroupId<BS>],
+    ) -> EnumMap<Player, NumStones<BS>> {
+        let mut removed = enum_map! { _ => NumStones::ZERO };
+        for pos in Pos::all_positions() {
+            if !dead.contains(&self.group_at(pos)) {
+                continue;
+            }
+            if let GroupInfo::PlayerGroup { owner, .. } = self.group_info(self.group_at(pos)) {
+                board.set(pos, None);
+                removed[*owner] += 1;
+            }
+        }
+        removed
+    }
+
+    /// The board this analysis was made for, rebuilt from the owner of each group. Lets the
+    /// readings that play out moves start from the analysis alone.
+    pub(crate) fn to_board(&self) -> Board<BS> {
+        let mut board = Board::new();
+        for pos in Pos::all_positions() {
+            if let GroupInfo::PlayerGroup { owner, .. } = self.group_info(self.group_at(pos)) {
+                board.set(pos, Some(*owner));
+            }
+        }
+        board
+    }
+
+    /// Text diagram of `board` with the group id after each cell, followed by a list of all groups
+    /// with their owner and liberties. Useful to look at the analysis next to the position when debugging.
+    pub fn to_debug_board(&self, board: &Board<BS>) -> String {
+        let width = self.group_info.len().saturating_sub(1).to_string().len();
+        let mut result = String::new();
+        for y in 0..<BS as BoardSize>::SIZE {
+            let row: Vec<String> = (0..<BS as BoardSize>::SIZE)
+                .map(|x| {
+                    let pos = Pos::from_xy(x, y);
+                    let glyph = StoneStyle::ASCII.glyph(board[pos]);
+                    let group = self.group_at(pos).into_usize();
+                    format!("{glyph}{group:<width$}")
+                })
+                .collect();
+            result.push_str(row.join(" ").trim_end());
+            result.push('\n');
+        }
+        for (group, info) in self.groups() {
+            let group = group.into_usize();
+            match info {
+                GroupInfo::PlayerGroup { owner, liberties } => {
+                    result.push_str(&format!("{group}: {owner}, {liberties} liberties\n"))
+                }
+                GroupInfo::EmptyStonesGroup => result.push_str(&format!("{group}: empty\n")),
+            }
+        }
+        result
+    }
 
-        liberties_and_owners
+    /// Number of distinct connected regions of empty cells
+    pub fn num_empty_regions(&self) -> usize {
+        self.group_info
+            .iter()
+            .filter(|info| matches!(info, GroupInfo::EmptyStonesGroup))
+            .count()
     }
 
     pub fn groups(
@@ -193,3 +403,725 @@ where
             .map(|(index, info)| (GroupId::from_usize(index), info))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Rng,
+        board::{BoardSize5x5, BoardSize7x7},
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn player_group<BS: BoardSize>(owner: Player, liberties: usize) -> GroupInfo<BS> {
+        GroupInfo::PlayerGroup {
+            owner,
+            liberties: NumStones::from_usize(liberties),
+        }
+    }
+
+    #[test]
+    fn group_info_by_id() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ○ ○ _ _ _
+            _ ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert_eq!(
+            &player_group(Player::Black, 2),
+            analysis.group_info(analysis.group_at(Pos::from_xy(1, 0)))
+        );
+        assert_eq!(
+            &player_group(Player::White, 3),
+            analysis.group_info(analysis.group_at(Pos::from_xy(1, 1)))
+        );
+        assert_eq!(
+            &GroupInfo::EmptyStonesGroup,
+            analysis.group_info(analysis.group_at(Pos::from_xy(4, 4)))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Group 3 doesn't exist, there are only 3 groups")]
+    fn group_info_of_unknown_group() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ○ ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        Analysis::analyze(&board).group_info(GroupId::from_usize(3));
+    }
+
+    /// Checks the analysis against a straightforward flood fill of each group, and
+    /// [Board::liberties_of_group_at] and [Board::liberty_points_of_group_at] against the analysis
+    fn assert_matches_flood_fill<BS: BoardSize>(board: &Board<BS>)
+    where
+        [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    {
+        let analysis = Analysis::analyze(board);
+        for pos in Pos::all_positions() {
+            let group = analysis.group_at(pos);
+            let region = board.flood_fill(pos, |cell| cell == board[pos]);
+            let group_size = Pos::all_positions()
+                .filter(|other| analysis.group_at(*other) == group)
+                .count();
+            assert_eq!(region.len(), group_size);
+            assert!(
+                region
+                    .iter()
+                    .all(|other| analysis.group_at(*other) == group)
+            );
+
+            let (expected_info, expected_liberty_points) = match board[pos] {
+                Some(owner) => {
+                    let mut liberties: Vec<Pos<BS>> = region
+                        .iter()
+                        .flat_map(|stone| stone.neighbors())
+                        .filter(|neighbor| board[*neighbor].is_none())
+                        .collect();
+                    liberties.sort();
+                    liberties.dedup();
+                    (player_group(owner, liberties.len()), liberties)
+                }
+                None => (GroupInfo::EmptyStonesGroup, Vec::new()),
+            };
+            assert_eq!(&expected_info, analysis.group_info(group));
+            assert_eq!(
+                expected_liberty_points,
+                board.liberty_points_of_group_at(pos)
+            );
+
+            let expected_liberties = match analysis.group_info(group) {
+                GroupInfo::PlayerGroup { liberties, .. } => Some(*liberties),
+                GroupInfo::EmptyStonesGroup => None,
+            };
+            assert_eq!(expected_liberties, board.liberties_of_group_at(pos));
+        }
+    }
+
+    #[test]
+    fn matches_flood_fill_on_all_fixtures() {
+        for fixture in [
+            r#"
+            _ ● _ ○ ○
+            ● ● ○ _ _
+            _ ○ ○ ● _
+            ○ _ _ ● _
+            _ _ _ _ ○
+            "#,
+            r#"
+            _ _ _ _ _
+            _ ● ● ● _
+            _ ● ○ ● _
+            _ ● ● ● _
+            _ _ _ _ _
+            "#,
+            r#"
+            _ ● _ _ _
+            ● ○ ○ ● _
+            _ ● ○ _ _
+            _ _ ● _ _
+            _ _ _ _ _
+            "#,
+            r#"
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+            "#,
+            r#"
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            "#,
+            r#"
+            ● ○ _ _ _
+            ○ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+        ] {
+            assert_matches_flood_fill(&Board::<BoardSize5x5>::from_str(fixture).unwrap());
+        }
+        assert_matches_flood_fill(&Board::<BoardSize5x5>::new());
+        assert_matches_flood_fill(
+            &Board::<BoardSize7x7>::from_str(
+                r#"
+                ○ ● ● ● ● ● ○
+                ○ _ _ ● _ _ ○
+                ○ ● ● ● ● ● ○
+                ○ ○ ○ ○ ○ ○ ○
+                ○ ● ● ● ● ● ○
+                ○ _ _ ● _ _ ○
+                ○ ● ● ● ● ● ○
+                "#,
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn group_info_of_complicated_board() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ● _ ○ ○
+            ● ● ○ _ _
+            _ ○ ○ ● _
+            ○ _ _ ● _
+            _ _ _ _ ○
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert_eq!(
+            vec![
+                GroupInfo::EmptyStonesGroup,
+                player_group(Player::White, 3),
+                GroupInfo::EmptyStonesGroup,
+                player_group(Player::Black, 3),
+                player_group(Player::Black, 5),
+                GroupInfo::EmptyStonesGroup,
+                GroupInfo::EmptyStonesGroup,
+                player_group(Player::White, 5),
+                player_group(Player::Black, 3),
+                GroupInfo::EmptyStonesGroup,
+                player_group(Player::Black, 2),
+            ],
+            analysis.group_info
+        );
+    }
+
+    #[test]
+    fn debug_board_of_complicated_board() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ● _ ○ ○
+            ● ● ○ _ _
+            _ ○ ○ ● _
+            ○ _ _ ● _
+            _ _ _ _ ○
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert_eq!(
+            "\
+.0  O1  .2  X3  X3
+O1  O1  X4  .5  .5
+.6  X4  X4  O7  .5
+X8  .9  .9  O7  .5
+.9  .9  .9  .9  X10
+0: empty
+1: White, 3 liberties
+2: empty
+3: Black, 3 liberties
+4: Black, 5 liberties
+5: empty
+6: empty
+7: White, 5 liberties
+8: Black, 3 liberties
+9: empty
+10: Black, 2 liberties
+",
+            analysis.to_debug_board(&board)
+        );
+    }
+
+    #[test]
+    fn adjacent_groups_of_surrounded_group() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ _ _ _ _
+            _ ● ● ● _
+            _ ● ○ ● _
+            _ ● ● ● _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        let black = analysis.group_at(Pos::from_xy(2, 2));
+        let white = analysis.group_at(Pos::from_xy(1, 1));
+        let outside = analysis.group_at(Pos::from_xy(0, 0));
+
+        let adjacent = analysis.adjacent_groups(black);
+        assert_eq!(1, adjacent.len());
+        assert!(adjacent.contains(&white));
+
+        let adjacent = analysis.adjacent_groups(white);
+        assert_eq!(2, adjacent.len());
+        assert!(adjacent.contains(&black));
+        assert!(adjacent.contains(&outside));
+    }
+
+    #[test]
+    fn adjacent_groups_of_group_with_liberties() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ● _ _ _
+            ● ○ ○ ● _
+            _ ● ○ _ _
+            _ _ ● _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        let black = analysis.group_at(Pos::from_xy(1, 1));
+        let adjacent = analysis.adjacent_groups(black);
+        // Five separate white stones plus the empty region around them, but not the corner at (0, 0)
+        assert_eq!(6, adjacent.len());
+        for pos in [(1, 0), (0, 1), (3, 1), (1, 2), (2, 3), (2, 0), (3, 2)] {
+            assert!(adjacent.contains(&analysis.group_at(Pos::from_xy(pos.0, pos.1))));
+        }
+        assert!(!adjacent.contains(&analysis.group_at(Pos::from_xy(0, 0))));
+        assert!(!adjacent.contains(&black));
+    }
+
+    #[test]
+    fn liberty_race() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ _ ○ _ _
+            ● ● ○ ● _
+            ○ ○ ○ ● ●
+            ● ● _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        let black = analysis.group_at(Pos::from_xy(2, 0));
+        let white = analysis.group_at(Pos::from_xy(0, 1));
+        // Black has three liberties, White two, and they share (1, 0)
+        assert_eq!(&player_group(Player::Black, 3), analysis.group_info(black));
+        assert_eq!(&player_group(Player::White, 2), analysis.group_info(white));
+        assert_eq!(Some(1), analysis.liberty_race(black, white));
+        assert_eq!(Some(-1), analysis.liberty_race(white, black));
+        assert_eq!(Some(0), analysis.liberty_race(black, black));
+    }
+
+    #[test]
+    fn liberty_race_with_empty_region() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ○ ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        let black = analysis.group_at(Pos::from_xy(0, 0));
+        let empty = analysis.group_at(Pos::from_xy(4, 4));
+        assert_eq!(None, analysis.liberty_race(black, empty));
+        assert_eq!(None, analysis.liberty_race(empty, black));
+        assert_eq!(None, analysis.liberty_race(empty, empty));
+    }
+
+    #[test]
+    fn are_connected() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ○ ○ _ ○ _
+            _ ○ _ _ _
+            _ ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert!(analysis.are_connected(Pos::from_xy(0, 0), Pos::from_xy(1, 1)));
+        assert!(analysis.are_connected(Pos::from_xy(1, 1), Pos::from_xy(0, 0)));
+        assert!(analysis.are_connected(Pos::from_xy(0, 0), Pos::from_xy(0, 0)));
+        // Same color, but not connected
+        assert!(!analysis.are_connected(Pos::from_xy(0, 0), Pos::from_xy(3, 0)));
+        // Different colors
+        assert!(!analysis.are_connected(Pos::from_xy(1, 1), Pos::from_xy(1, 2)));
+        // A stone and an empty point, and two points of the same empty region
+        assert!(!analysis.are_connected(Pos::from_xy(1, 0), Pos::from_xy(2, 0)));
+        assert!(!analysis.are_connected(Pos::from_xy(2, 0), Pos::from_xy(4, 4)));
+    }
+
+    #[test]
+    fn reanalyze_reuses_analysis() {
+        let mut rng = Rng::new(7);
+        let mut board = Board::<BoardSize7x7>::new();
+        let mut analysis = Analysis::analyze(&board);
+        // Fill the board and clear it again, so the number of groups both grows and shrinks
+        for step in 0..200 {
+            let pos = Pos::from_index(rng.below(7 * 7));
+            let value = match (step < 100, rng.below(2)) {
+                (false, _) => None,
+                (true, 0) => Some(Player::Black),
+                (true, _) => Some(Player::White),
+            };
+            board.set(pos, value);
+            analysis.reanalyze(&board);
+            assert_eq!(Analysis::analyze(&board), analysis, "after step {step}");
+        }
+    }
+
+    mod incremental_update {
+        use crate::{
+            Rng,
+            board::{BoardSize5x5, BoardSize9x9},
+        };
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn toggle_dead_group() {
+            let mut board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ ○ ● _ _
+                ○ ○ ● _ _
+                ● ● ● _ ○
+                _ _ _ _ _
+                _ ○ _ _ _
+            "#,
+            )
+            .unwrap();
+            let mut analysis = Analysis::analyze(&board);
+            let dead = [Pos::from_xy(1, 0), Pos::from_xy(0, 1), Pos::from_xy(1, 1)];
+            for pos in dead {
+                analysis.remove_stone(&mut board, pos);
+                assert_eq!(Analysis::analyze(&board), analysis);
+            }
+            // The white group gained the liberties of the dead stones
+            assert_eq!(
+                &player_group(Player::White, 9),
+                analysis.group_info(analysis.group_at(Pos::from_xy(2, 0)))
+            );
+            for pos in dead {
+                analysis.add_stone(&mut board, pos, Player::Black);
+                assert_eq!(Analysis::analyze(&board), analysis);
+            }
+        }
+
+        #[test]
+        fn removing_stone_splits_group() {
+            let mut board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ _ ○ _ _
+                _ _ ○ _ _
+                ○ ○ ○ ○ ○
+                _ _ ○ _ _
+                _ _ ○ _ _
+            "#,
+            )
+            .unwrap();
+            let mut analysis = Analysis::analyze(&board);
+            analysis.remove_stone(&mut board, Pos::from_xy(2, 2));
+            assert_eq!(Analysis::analyze(&board), analysis);
+            // Four arms, four empty corners and the empty center
+            assert_eq!(9, analysis.groups().len());
+        }
+
+        #[test]
+        fn adding_stone_splits_empty_region() {
+            let mut board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ _ ○ _ _
+                _ _ ○ _ _
+                _ _ _ _ _
+                _ _ ○ _ _
+                _ _ ○ _ _
+            "#,
+            )
+            .unwrap();
+            let mut analysis = Analysis::analyze(&board);
+            analysis.add_stone(&mut board, Pos::from_xy(2, 2), Player::White);
+            assert_eq!(Analysis::analyze(&board), analysis);
+            assert_eq!(2, analysis.num_empty_regions());
+        }
+
+        fn random_changes<BS: BoardSize>(seed: u64, num_changes: usize)
+        where
+            [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+            [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+        {
+            let mut rng = Rng::new(seed);
+            let mut board = Board::<BS>::new();
+            let mut analysis = Analysis::analyze(&board);
+            for _ in 0..num_changes {
+                let pos =
+                    Pos::from_index(rng.below(<BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE));
+                if board[pos].is_some() {
+                    analysis.remove_stone(&mut board, pos);
+                } else {
+                    let player = if rng.below(2) == 0 {
+                        Player::Black
+                    } else {
+                        Player::White
+                    };
+                    analysis.add_stone(&mut board, pos, player);
+                }
+                assert_eq!(Analysis::analyze(&board), analysis);
+            }
+        }
+
+        #[test]
+        fn matches_full_analysis_after_random_changes() {
+            for seed in 0..20 {
+                random_changes::<BoardSize5x5>(seed, 60);
+                random_changes::<BoardSize9x9>(seed, 150);
+            }
+        }
+    }
+
+    mod liberties_after_move {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        const BOARD: &str = r#"
+            _ ○ ● _ _
+            ○ ○ ● _ _
+            ● ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#;
+
+        fn liberties_after_move(board: &str, x: usize, y: usize, player: Player) -> Option<usize> {
+            let board = Board::<BoardSize5x5>::from_str(board).unwrap();
+            Analysis::analyze(&board)
+                .liberties_after_move(&board, Pos::from_xy(x, y), player)
+                .map(|liberties| liberties.into_usize())
+        }
+
+        #[test]
+        fn single_stone() {
+            assert_eq!(Some(4), liberties_after_move(BOARD, 3, 3, Player::Black));
+            assert_eq!(Some(2), liberties_after_move(BOARD, 4, 4, Player::White));
+        }
+
+        #[test]
+        fn extending_a_group() {
+            // The white group at the top has 3 liberties. Extending sideways fills one and adds two.
+            assert_eq!(Some(4), liberties_after_move(BOARD, 3, 1, Player::White));
+        }
+
+        #[test]
+        fn connecting_two_groups() {
+            // Connects both white groups, and fills the liberty they share
+            assert_eq!(Some(6), liberties_after_move(BOARD, 2, 2, Player::White));
+        }
+
+        #[test]
+        fn self_atari() {
+            let board = r#"
+                _ ● _ _ _
+                ● _ ● _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#;
+            assert_eq!(Some(1), liberties_after_move(board, 1, 1, Player::Black));
+        }
+
+        #[test]
+        fn capturing() {
+            // Capturing the black group in the corner frees up its stones as liberties
+            assert_eq!(Some(2), liberties_after_move(BOARD, 0, 0, Player::White));
+            // Without the capture, this would be suicide
+            let board = r#"
+                ○ ● _ _ _
+                _ ○ _ _ _
+                ○ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#;
+            assert_eq!(Some(1), liberties_after_move(board, 0, 1, Player::White));
+        }
+
+        #[test]
+        fn illegal_moves() {
+            // Occupied
+            assert_eq!(None, liberties_after_move(BOARD, 1, 1, Player::Black));
+            assert_eq!(None, liberties_after_move(BOARD, 1, 1, Player::White));
+            // Suicide
+            let board = r#"
+                _ ● _ _ _
+                ● _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#;
+            assert_eq!(None, liberties_after_move(board, 0, 0, Player::Black));
+        }
+    }
+
+    #[test]
+    fn remove_dead_groups() {
+        let mut board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ● _ ○ ○
+            ● ● ○ _ _
+            _ ○ ○ ● _
+            ○ _ _ ● _
+            _ _ _ _ ○
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        let dead = [
+            analysis.group_at(Pos::from_xy(0, 0)), // empty, ignored
+            analysis.group_at(Pos::from_xy(1, 1)),
+            analysis.group_at(Pos::from_xy(3, 3)),
+            analysis.group_at(Pos::from_xy(4, 4)),
+        ];
+        let removed = analysis.remove_dead_groups(&mut board, &dead);
+        assert_eq!(NumStones::ONE, removed[Player::Black]);
+        assert_eq!(NumStones::from_usize(5), removed[Player::White]);
+        assert_eq!(
+            Board::from_str(
+                r#"
+                _ _ _ ○ ○
+                _ _ ○ _ _
+                _ ○ ○ _ _
+                ○ _ _ _ _
+                _ _ _ _ _
+            "#
+            )
+            .unwrap(),
+            board
+        );
+    }
+
+    #[test]
+    fn group_info_of_merged_groups() {
+        let board = Board::<BoardSize7x7>::from_str(
+            r#"
+            ○ ● ● ● ● ● ○
+            ○ _ _ ● _ _ ○
+            ○ ● ● ● ● ● ○
+            ○ ○ ○ ○ ○ ○ ○
+            ○ ● ● ● ● ● ○
+            ○ _ _ ● _ _ ○
+            ○ ● ● ● ● ● ○
+            "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert_eq!(
+            vec![
+                player_group(Player::Black, 4),
+                player_group(Player::White, 4),
+                GroupInfo::EmptyStonesGroup,
+                GroupInfo::EmptyStonesGroup,
+                player_group(Player::White, 4),
+                GroupInfo::EmptyStonesGroup,
+                GroupInfo::EmptyStonesGroup,
+            ],
+            analysis.group_info
+        );
+    }
+
+    #[test]
+    fn empty_board_has_one_empty_region() {
+        let analysis = Analysis::analyze(&Board::<BoardSize5x5>::new());
+        assert_eq!(1, analysis.num_empty_regions());
+    }
+
+    #[test]
+    fn wall_splits_board_into_two_empty_regions() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+            _ _ ○ _ _
+        "#,
+        )
+        .unwrap();
+        assert_eq!(2, Analysis::analyze(&board).num_empty_regions());
+    }
+
+    #[test]
+    fn full_board_has_no_empty_regions() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+            ○ ○ ● ● ●
+        "#,
+        )
+        .unwrap();
+        assert_eq!(0, Analysis::analyze(&board).num_empty_regions());
+    }
+
+    #[test]
+    fn group_in_atari_is_not_enclosed() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ● ○ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert!(!analysis.is_enclosed(analysis.group_at(Pos::from_xy(0, 0))));
+    }
+
+    #[test]
+    fn group_without_liberties_is_enclosed() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            ● ○ _ _ _
+            ○ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert!(analysis.is_enclosed(analysis.group_at(Pos::from_xy(0, 0))));
+        assert!(!analysis.is_enclosed(analysis.group_at(Pos::from_xy(1, 0))));
+    }
+
+    #[test]
+    fn empty_region_is_not_enclosed() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ○ _ _ _
+            ○ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+        )
+        .unwrap();
+        let analysis = Analysis::analyze(&board);
+        assert!(!analysis.is_enclosed(analysis.group_at(Pos::from_xy(0, 0))));
+    }
+}
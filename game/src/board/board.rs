@@ -1,10 +1,19 @@
-use bitvec::{array::BitArray, order::Lsb0};
+use bitvec::{array::BitArray, bitvec, order::Lsb0};
+use derive_where::derive_where;
 use std::fmt::Debug;
 use std::ops::Index;
 
-use super::{PlaceStoneError, Player, Pos, pos::BoardSize};
+use crate::{
+    group_stones::{GroupId, group_connected_stones},
+    utils::SmallSet,
+};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use super::{
+    BoardBytesError, BoardParseError, NumStones, PlaceStoneError, Player, Pos, StoneStyle,
+    pos::BoardSize, zobrist::stone_key,
+};
+
+#[derive_where(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Board<BS: BoardSize>
 where
     [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
@@ -24,17 +33,7 @@ where
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Board(")?;
-        for y in 0..<BS as BoardSize>::SIZE {
-            for x in 0..<BS as BoardSize>::SIZE {
-                let cell = self[Pos::from_xy(x, y)];
-                match cell {
-                    Some(Player::Black) => write!(f, "● ")?,
-                    Some(Player::White) => write!(f, "○ ")?,
-                    None => write!(f, "_ ")?,
-                }
-            }
-            writeln!(f)?;
-        }
+        write!(f, "{}", self.to_ascii(&StoneStyle::UNICODE))?;
         writeln!(f, ")")?;
         Ok(())
     }
@@ -57,6 +56,14 @@ where
         self._set(index, value);
     }
 
+    /// Sets several cells at once, e.g. to set up handicap stones or an SGF position.
+    /// Later entries win if a position appears more than once.
+    pub fn set_many(&mut self, stones: impl IntoIterator<Item = (Pos<BS>, Option<Player>)>) {
+        for (pos, value) in stones {
+            self._set(Self::index(pos), value);
+        }
+    }
+
     fn _set(&mut self, index: usize, value: Option<Player>) {
         self.cells.set(index, value.is_some());
         self.cells.set(
@@ -74,6 +81,20 @@ where
         self._is_occupied(Self::index(pos))
     }
 
+    /// Whether both boards have the same cells everywhere except at the `ignore`d points, e.g. to match
+    /// a solution against a problem while ignoring the rest of the board.
+    pub fn equals_ignoring(&self, other: &Self, ignore: &[Pos<BS>]) -> bool {
+        Pos::all_positions().all(|pos| self[pos] == other[pos] || ignore.contains(&pos))
+    }
+
+    /// Number of empty orthogonal neighbors of `pos`. For a single stone, these are its liberties,
+    /// so this is a cheap check that doesn't need an [crate::Analysis].
+    pub fn adjacent_empty_count(&self, pos: Pos<BS>) -> usize {
+        pos.neighbors()
+            .filter(|neighbor| !self.is_occupied(*neighbor))
+            .count()
+    }
+
     fn _is_occupied(&self, index: usize) -> bool {
         self.cells[index]
     }
@@ -93,12 +114,309 @@ where
         Ok(())
     }
 
+    /// Places a stone for `player` at `pos` and removes the opponent groups left without liberties,
+    /// returning the captured positions. A lightweight alternative to [crate::Game] for tools that
+    /// don't need its bookkeeping: there is no turn order and no ko rule. The board is unchanged if
+    /// `pos` is occupied or the move would be suicide. Like [Self::liberties_of_group_at], this only
+    /// looks at the groups next to `pos`, so it's cheap enough for searches that play out many moves.
+    pub fn place_and_capture(
+        &mut self,
+        pos: Pos<BS>,
+        player: Player,
+    ) -> Result<SmallSet<[Pos<BS>; 4]>, PlaceStoneError> {
+        self.set_if_empty(pos, player)?;
+        let opponent = player.other_player();
+        let mut captured = SmallSet::new();
+        for neighbor in pos.neighbors() {
+            // Once a group is removed, its other neighbors of `pos` are empty and skipped
+            if self[neighbor] == Some(opponent)
+                && self.liberties_of_group_at(neighbor) == Some(NumStones::ZERO)
+            {
+                for stone in self.flood_fill(neighbor, |cell| cell == Some(opponent)) {
+                    self.set(stone, None);
+                    captured.insert(stone);
+                }
+            }
+        }
+        if self.liberties_of_group_at(pos) == Some(NumStones::ZERO) {
+            // Nothing was captured, otherwise the stone would have a liberty
+            self.set(pos, None);
+            return Err(PlaceStoneError::Suicide);
+        }
+        Ok(captured)
+    }
+
+    /// The stones on the board split into chains of connected stones of the same color, e.g. to hand
+    /// the position to other engines. Chains are ordered by their first stone and each chain's
+    /// positions are sorted by index.
+    pub fn chains(&self) -> Vec<(Player, Vec<Pos<BS>>)>
+    where
+        [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+    {
+        let grouped = group_connected_stones(self);
+        let mut groups: Vec<Option<(Player, Vec<Pos<BS>>)>> =
+            (0..grouped.num_groups().into_usize())
+                .map(|group| {
+                    grouped
+                        .owner(GroupId::from_usize(group))
+                        .map(|owner| (owner, Vec::new()))
+                })
+                .collect();
+        for pos in Pos::all_positions() {
+            if let Some((_, stones)) = &mut groups[grouped.group_at(pos).into_usize()] {
+                stones.push(pos);
+            }
+        }
+        groups.into_iter().flatten().collect()
+    }
+
+    /// All positions connected to `start` through orthogonal neighbors whose cells satisfy
+    /// `predicate`, sorted by index. Empty if `start` itself doesn't satisfy it.
+    pub fn flood_fill(
+        &self,
+        start: Pos<BS>,
+        predicate: impl Fn(Option<Player>) -> bool,
+    ) -> Vec<Pos<BS>> {
+        if !predicate(self[start]) {
+            return Vec::new();
+        }
+
+        let mut visited = bitvec![0; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
+        visited.set(start.index(), true);
+        let mut result = vec![start];
+        let mut todo = vec![start];
+        while let Some(pos) = todo.pop() {
+            for neighbor in pos.neighbors() {
+                if !visited[neighbor.index()] && predicate(self[neighbor]) {
+                    visited.set(neighbor.index(), true);
+                    result.push(neighbor);
+                    todo.push(neighbor);
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
+    /// Number of liberties of the group containing the stone at `pos`, or `None` if `pos` is empty.
+    ///
+    /// This only visits the group and its neighbors instead of analyzing the whole board like
+    /// [crate::Analysis] does, so it's cheaper when only a single group matters, e.g. to check a
+    /// move while simulating.
+    pub fn liberties_of_group_at(&self, pos: Pos<BS>) -> Option<NumStones<BS>> {
+        let owner = self[pos]?;
+        let mut liberties = NumStones::ZERO;
+        self._for_each_liberty(pos, owner, |_| liberties += NumStones::ONE);
+        Some(liberties)
+    }
+
+    /// The liberties of the group containing the stone at `pos`, sorted by index. Empty if `pos`
+    /// is empty. Visits the same points as [Self::liberties_of_group_at].
+    pub fn liberty_points_of_group_at(&self, pos: Pos<BS>) -> Vec<Pos<BS>> {
+        let Some(owner) = self[pos] else {
+            return Vec::new();
+        };
+        let mut liberties = Vec::new();
+        self._for_each_liberty(pos, owner, |liberty| liberties.push(liberty));
+        liberties.sort();
+        liberties
+    }
+
+    /// Calls `on_liberty` once for each liberty of the group of `owner` containing `pos`
+    fn _for_each_liberty(&self, pos: Pos<BS>, owner: Player, mut on_liberty: impl FnMut(Pos<BS>)) {
+        // Stones and liberties are both marked, so each position is only looked at once
+        let mut visited = bitvec![0; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
+        visited.set(pos.index(), true);
+        let mut todo = vec![pos];
+        while let Some(stone) = todo.pop() {
+            for neighbor in stone.neighbors() {
+                if visited[neighbor.index()] {
+                    continue;
+                }
+                match self[neighbor] {
+                    None => {
+                        visited.set(neighbor.index(), true);
+                        on_liberty(neighbor);
+                    }
+                    Some(player) if player == owner => {
+                        visited.set(neighbor.index(), true);
+                        todo.push(neighbor);
+                    }
+                    // Opponent stones may border several stones of the group, leave them unmarked
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    /// Whether `pos` is an eye of `player`, i.e. an empty point whose orthogonal neighbors are all
+    /// `player`'s stones. To rule out false eyes, the opponent may own at most one diagonal in the
+    /// center of the board, and none on the edge.
+    pub fn is_eye(&self, pos: Pos<BS>, player: Player) -> bool {
+        if self.is_occupied(pos) {
+            return false;
+        }
+        let surrounded = pos
+            .neighbors()
+            .all(|neighbor| self[neighbor] == Some(player));
+        if !surrounded {
+            return false;
+        }
+        let diagonals = [
+            pos.up().and_then(|up| up.left()),
+            pos.up().and_then(|up| up.right()),
+            pos.down().and_then(|down| down.left()),
+            pos.down().and_then(|down| down.right()),
+        ];
+        let num_on_board = diagonals.iter().flatten().count();
+        let num_opponent = diagonals
+            .into_iter()
+            .flatten()
+            .filter(|diagonal| self[*diagonal] == Some(player.other_player()))
+            .count();
+        if num_on_board == 4 {
+            num_opponent <= 1
+        } else {
+            num_opponent == 0
+        }
+    }
+
     #[inline]
     fn index(pos: Pos<BS>) -> usize {
         let pos_index = pos.index();
         2 * pos_index
     }
 
+    /// All stones on the board, ordered by position
+    pub fn occupied(&self) -> impl Iterator<Item = (Pos<BS>, Player)> + '_ {
+        // Occupied bits are at even indices, the odd bits only store the color
+        self.cells
+            .iter_ones()
+            .filter(|index| index % 2 == 0)
+            .map(|index| {
+                let player = if self._is_black(index) {
+                    Player::Black
+                } else {
+                    Player::White
+                };
+                (Pos::from_index(index / 2), player)
+            })
+    }
+
+    /// Text diagram of the board with one line per row, drawing cells with the glyphs from `style`
+    pub fn to_ascii(&self, style: &StoneStyle) -> String {
+        let mut result = String::new();
+        for y in 0..<BS as BoardSize>::SIZE {
+            for x in 0..<BS as BoardSize>::SIZE {
+                result.push_str(style.glyph(self[Pos::from_xy(x, y)]));
+                result.push(' ');
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Cells of the `width` x `height` rectangle starting at `top_left`, as rows from top to bottom.
+    /// Panics if the rectangle doesn't fit on the board.
+    pub fn subregion(
+        &self,
+        top_left: Pos<BS>,
+        width: usize,
+        height: usize,
+    ) -> Vec<Vec<Option<Player>>> {
+        assert!(
+            top_left.x() + width <= <BS as BoardSize>::SIZE
+                && top_left.y() + height <= <BS as BoardSize>::SIZE,
+            "Subregion out of bounds"
+        );
+        (top_left.y()..top_left.y() + height)
+            .map(|y| {
+                (top_left.x()..top_left.x() + width)
+                    .map(|x| self[Pos::from_xy(x, y)])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Top-left and bottom-right corner of the smallest rectangle containing all stones, both inclusive,
+    /// or `None` if the board is empty. E.g. to crop a diagram with [Self::subregion].
+    pub fn stones_bounding_box(&self) -> Option<(Pos<BS>, Pos<BS>)> {
+        let (first, _) = self.occupied().next()?;
+        let (min_x, min_y, max_x, max_y) = self.occupied().fold(
+            (first.x(), first.y(), first.x(), first.y()),
+            |(min_x, min_y, max_x, max_y), (pos, _)| {
+                (
+                    min_x.min(pos.x()),
+                    min_y.min(pos.y()),
+                    max_x.max(pos.x()),
+                    max_y.max(pos.y()),
+                )
+            },
+        );
+        Some((Pos::from_xy(min_x, min_y), Pos::from_xy(max_x, max_y)))
+    }
+
+    /// Compact binary form of the board, with the same 2 bits per cell as the in-memory representation.
+    /// This takes `ceil(2 * SIZE * SIZE / 8)` bytes, e.g. 91 bytes for 19x19. See [Self::from_bytes].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; Self::_num_bytes()];
+        for index in self.cells.iter_ones() {
+            bytes[index / 8] |= 1 << (index % 8);
+        }
+        bytes
+    }
+
+    /// Reads a board written by [Self::to_bytes] for the same board size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BoardBytesError> {
+        let num_bits = 2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE;
+        let expected = Self::_num_bytes();
+        if bytes.len() != expected {
+            return Err(BoardBytesError::WrongLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        let bit = |index: usize| (bytes[index / 8] >> (index % 8)) & 1 == 1;
+        if (num_bits..expected * 8).any(bit) {
+            return Err(BoardBytesError::NonZeroPadding);
+        }
+
+        let mut board = Self::new();
+        for index in (0..num_bits).filter(|index| bit(*index)) {
+            board.cells.set(index, true);
+        }
+        for pos in Pos::all_positions() {
+            let index = Self::index(pos);
+            if !board._is_occupied(index) && board._is_black(index) {
+                return Err(BoardBytesError::InvalidCell {
+                    pos: (pos.x(), pos.y()),
+                });
+            }
+        }
+        Ok(board)
+    }
+
+    fn _num_bytes() -> usize {
+        (2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE).div_ceil(8)
+    }
+
+    /// Zobrist hash of the position. Equal boards have equal hashes, and different boards
+    /// almost certainly have different ones. Whose turn it is isn't part of the hash.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.occupied()
+            .fold(0, |hash, (pos, player)| hash ^ stone_key(pos, player))
+    }
+
+    /// All empty cells, ordered by position
+    pub fn empty(&self) -> impl Iterator<Item = Pos<BS>> + '_ {
+        // The backing array can have unused bits at the end, which are always zero
+        self.cells
+            .iter_zeros()
+            .take_while(|index| *index < 2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)
+            .filter(|index| index % 2 == 0)
+            .map(|index| Pos::from_index(index / 2))
+    }
+
     #[cfg(test)]
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = (Pos<BS>, Option<Player>)>
@@ -112,34 +430,83 @@ where
         })
     }
 
-    pub fn from_str(input: &str) -> Result<Self, String> {
+    /// Parses a board with one character per cell, separated by any whitespace:
+    /// '○' for black, '●' for white and '_' for an empty cell.
+    pub fn from_str(input: &str) -> Result<Self, BoardParseError> {
+        Self::_parse(input, |c| match c {
+            '_' => Some(None),
+            '○' => Some(Some(Player::Black)),
+            '●' => Some(Some(Player::White)),
+            _ => None,
+        })
+    }
+
+    /// Like [Board::from_str], but also accepts the glyphs other sources use for diagrams:
+    ///
+    /// - Black: `X`, `x`, `B`, `#` or '●'
+    /// - White: `O`, `o`, `W` or '○'
+    /// - Empty: `_`, `.` or `+`
+    ///
+    /// Unlike in [Board::from_str], '●' is black and '○' is white like in [StoneStyle::UNICODE],
+    /// so this reads back what [Board::to_ascii] writes.
+    pub fn parse(input: &str) -> Result<Self, BoardParseError> {
+        Self::_parse(input, |c| match c {
+            '_' | '.' | '+' => Some(None),
+            'X' | 'x' | 'B' | '#' | '●' => Some(Some(Player::Black)),
+            'O' | 'o' | 'W' | '○' => Some(Some(Player::White)),
+            _ => None,
+        })
+    }
+
+    /// `cell_from_char` returns the content of a cell, or `None` if the character isn't a valid cell
+    fn _parse(
+        input: &str,
+        cell_from_char: impl Fn(char) -> Option<Option<Player>>,
+    ) -> Result<Self, BoardParseError> {
         let mut board = Board::<BS>::new();
         let mut input = input.chars().peekable();
         for y in 0..<BS as BoardSize>::SIZE {
             for x in 0..<BS as BoardSize>::SIZE {
                 trim_whitespaces(&mut input);
                 let cell_value = match input.next() {
-                    Some('_') => None,
-                    Some('○') => Some(Player::Black),
-                    Some('●') => Some(Player::White),
-                    char => {
-                        return Err(format!(
-                            "Invalid input format: expected '○' for black, '●' for white, or ' ' for empty cell but got {char:?}",
-                        ));
-                    }
+                    Some(found) => cell_from_char(found)
+                        .ok_or(BoardParseError::InvalidChar { found, pos: (x, y) })?,
+                    None => return Err(BoardParseError::TooFewCells),
                 };
                 board.set(Pos::from_xy(x, y), cell_value);
             }
             trim_whitespaces(&mut input);
         }
         trim_whitespaces(&mut input);
-        if let Some(char) = input.next() {
-            return Err(format!(
-                "Invalid input format: extra characters found after board: {char:?}"
-            ));
+        if input.next().is_some() {
+            return Err(BoardParseError::ExtraCharacters);
         }
         Ok(board)
     }
+
+    /// Like [Board::from_str], but ignores coordinate labels as they're common in diagrams
+    /// pasted from websites: lines with column headers (`A B C ...`) and row numbers before
+    /// or after each row.
+    pub fn from_labeled_str(input: &str) -> Result<Self, BoardParseError> {
+        let mut cells = String::new();
+        for line in input.lines() {
+            let is_column_header = line
+                .chars()
+                .all(|c| c.is_ascii_alphabetic() || c.is_whitespace());
+            if is_column_header {
+                continue;
+            }
+            for token in line.split_whitespace() {
+                let is_row_number = token.chars().all(|c| c.is_ascii_digit());
+                if !is_row_number {
+                    cells.push_str(token);
+                    cells.push(' ');
+                }
+            }
+            cells.push('\n');
+        }
+        Self::from_str(&cells)
+    }
 }
 
 impl<BS: BoardSize> Index<Pos<BS>> for Board<BS>
@@ -175,7 +542,10 @@ fn trim_whitespaces(input: &mut std::iter::Peekable<std::str::Chars>) {
 
 #[cfg(test)]
 mod tests {
-    use crate::board::{BoardSize9x9, BoardSize13x13, BoardSize19x19, Player};
+    use crate::board::{
+        BoardSize3x3, BoardSize5x5, BoardSize9x9, BoardSize13x13, BoardSize19x19, BoardSize21x21,
+        Player,
+    };
 
     use super::*;
 
@@ -186,6 +556,32 @@ mod tests {
         assert_eq!(24, std::mem::size_of::<Board<BoardSize9x9>>());
     }
 
+    #[test]
+    fn index_types() {
+        assert_eq!(1, std::mem::size_of::<Pos<BoardSize13x13>>());
+        assert_eq!(2, std::mem::size_of::<Pos<BoardSize19x19>>());
+        assert_eq!(2, std::mem::size_of::<Pos<BoardSize21x21>>());
+    }
+
+    #[test]
+    fn board_21x21() {
+        let mut board = Board::<BoardSize21x21>::new();
+        let last = Pos::from_xy(20, 20);
+        assert_eq!(440, last.index());
+        board.set(last, Some(Player::Black));
+        board.set(Pos::from_xy(19, 20), Some(Player::White));
+        assert_eq!(Some(Player::Black), board[last]);
+        assert_eq!(Some(Player::White), board[Pos::from_index(439)]);
+        assert_eq!(None, board[Pos::from_xy(20, 19)]);
+        assert_eq!(
+            441,
+            Pos::<BoardSize21x21>::all_positions()
+                .map(|pos| pos.index())
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        );
+    }
+
     #[test]
     fn empty_board() {
         let board = Board::<BoardSize13x13>::new();
@@ -208,6 +604,283 @@ mod tests {
         }
     }
 
+    #[test]
+    fn occupied_and_empty() {
+        let board = Board::<BoardSize9x9>::from_str(
+            r#"
+            ○ _ _ _ _ _ _ _ ●
+            _ _ _ _ _ _ _ _ _
+            _ _ ○ ● _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            ● _ _ _ _ _ _ _ ○
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                (Pos::from_xy(0, 0), Player::Black),
+                (Pos::from_xy(8, 0), Player::White),
+                (Pos::from_xy(2, 2), Player::Black),
+                (Pos::from_xy(3, 2), Player::White),
+                (Pos::from_xy(4, 4), Player::Black),
+                (Pos::from_xy(0, 8), Player::White),
+                (Pos::from_xy(8, 8), Player::Black),
+            ],
+            board.occupied().collect::<Vec<_>>()
+        );
+        let empty: Vec<_> = board.empty().collect();
+        assert_eq!(81 - 7, empty.len());
+        assert_eq!(Pos::from_xy(1, 0), empty[0]);
+        assert_eq!(Pos::from_xy(7, 8), *empty.last().unwrap());
+        assert!(empty.iter().all(|pos| board[*pos].is_none()));
+    }
+
+    #[test]
+    fn occupied_and_empty_on_full_and_empty_board() {
+        let board = Board::<BoardSize9x9>::new();
+        assert_eq!(0, board.occupied().count());
+        assert_eq!(81, board.empty().count());
+
+        let mut board = Board::<BoardSize9x9>::new();
+        for pos in Pos::all_positions() {
+            board.set(pos, Some(Player::White));
+        }
+        assert_eq!(81, board.occupied().count());
+        assert_eq!(0, board.empty().count());
+    }
+
+    #[test]
+    fn to_ascii() {
+        let mut board = Board::<BoardSize3x3>::new();
+        board.set(Pos::from_xy(0, 0), Some(Player::Black));
+        board.set(Pos::from_xy(2, 1), Some(Player::White));
+        assert_eq!(
+            "X . . \n. . O \n. . . \n",
+            board.to_ascii(&StoneStyle::ASCII)
+        );
+        assert_eq!(
+            "● _ _ \n_ _ ○ \n_ _ _ \n",
+            board.to_ascii(&StoneStyle::default())
+        );
+        let custom = StoneStyle {
+            black: "b",
+            white: "w",
+            empty: "-",
+        };
+        assert_eq!("b - - \n- - w \n- - - \n", board.to_ascii(&custom));
+    }
+
+    #[test]
+    fn subregion() {
+        let board = Board::<BoardSize9x9>::from_str(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ ○ _ _
+            _ _ _ _ _ _ ● ○ _
+            _ _ _ _ _ _ _ _ ●
+            _ _ _ _ _ ○ _ _ _
+        "#,
+        )
+        .unwrap();
+        let black = Some(Player::Black);
+        let white = Some(Player::White);
+        assert_eq!(
+            vec![
+                vec![white, black, None],
+                vec![None, None, white],
+                vec![None, None, None],
+            ],
+            board.subregion(Pos::from_xy(6, 6), 3, 3)
+        );
+        assert_eq!(
+            vec![vec![black, None, None, None]],
+            board.subregion(Pos::from_xy(5, 8), 4, 1)
+        );
+        assert_eq!(
+            Vec::<Vec<Option<Player>>>::new(),
+            board.subregion(Pos::from_xy(0, 0), 3, 0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Subregion out of bounds")]
+    fn subregion_out_of_bounds() {
+        Board::<BoardSize9x9>::new().subregion(Pos::from_xy(7, 0), 3, 3);
+    }
+
+    #[test]
+    fn stones_bounding_box_of_empty_board() {
+        assert_eq!(None, Board::<BoardSize9x9>::new().stones_bounding_box());
+    }
+
+    #[test]
+    fn stones_bounding_box_of_single_stone() {
+        let mut board = Board::<BoardSize9x9>::new();
+        board.set(Pos::from_xy(8, 0), Some(Player::White));
+        assert_eq!(
+            Some((Pos::from_xy(8, 0), Pos::from_xy(8, 0))),
+            board.stones_bounding_box()
+        );
+    }
+
+    #[test]
+    fn stones_bounding_box_of_spread_out_stones() {
+        let board = Board::<BoardSize9x9>::from_str(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ● _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ ○ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ ● _ _
+            _ _ _ ○ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#,
+        )
+        .unwrap();
+        let (top_left, bottom_right) = board.stones_bounding_box().unwrap();
+        assert_eq!(Pos::from_xy(1, 1), top_left);
+        assert_eq!(Pos::from_xy(6, 6), bottom_right);
+        // The cropped region contains all stones
+        let cropped = board.subregion(top_left, 6, 6);
+        assert_eq!(
+            4,
+            cropped
+                .iter()
+                .flatten()
+                .filter(|cell| cell.is_some())
+                .count()
+        );
+    }
+
+    mod bytes {
+        use crate::board::{BoardSize5x5, BoardSize19x19};
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        fn assert_round_trip<BS: BoardSize>(board: &Board<BS>)
+        where
+            [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+        {
+            let bytes = board.to_bytes();
+            let size = <BS as BoardSize>::SIZE;
+            assert_eq!((2 * size * size).div_ceil(8), bytes.len());
+            assert_eq!(*board, Board::from_bytes(&bytes).unwrap());
+        }
+
+        #[test]
+        fn round_trip() {
+            assert_round_trip(&Board::<BoardSize5x5>::new());
+            assert_round_trip(
+                &Board::<BoardSize5x5>::from_str(
+                    r#"
+                    ○ ● _ _ ●
+                    _ ○ ● _ _
+                    _ _ _ _ _
+                    ● _ ○ ○ _
+                    _ _ _ ● ○
+                    "#,
+                )
+                .unwrap(),
+            );
+            let mut board = Board::<BoardSize9x9>::new();
+            for pos in Pos::all_positions().step_by(3) {
+                let player = if pos.index() % 2 == 0 {
+                    Player::Black
+                } else {
+                    Player::White
+                };
+                board.set(pos, Some(player));
+            }
+            assert_round_trip(&board);
+            assert_round_trip(&Board::<BoardSize19x19>::new());
+        }
+
+        #[test]
+        fn byte_length() {
+            assert_eq!(7, Board::<BoardSize5x5>::new().to_bytes().len());
+            assert_eq!(21, Board::<BoardSize9x9>::new().to_bytes().len());
+            assert_eq!(91, Board::<BoardSize19x19>::new().to_bytes().len());
+        }
+
+        #[test]
+        fn wrong_length() {
+            assert_eq!(
+                Err(BoardBytesError::WrongLength {
+                    expected: 7,
+                    actual: 6
+                }),
+                Board::<BoardSize5x5>::from_bytes(&[0; 6])
+            );
+        }
+
+        #[test]
+        fn color_without_stone() {
+            let mut bytes = vec![0; 7];
+            // Cell 1 is (1, 0), its color bit is bit 3
+            bytes[0] = 0b1000;
+            assert_eq!(
+                Err(BoardBytesError::InvalidCell { pos: (1, 0) }),
+                Board::<BoardSize5x5>::from_bytes(&bytes)
+            );
+        }
+
+        #[test]
+        fn non_zero_padding() {
+            let mut bytes = vec![0; 7];
+            // 25 cells use 50 bits, the last 6 bits are padding
+            bytes[6] = 0b1000_0000;
+            assert_eq!(
+                Err(BoardBytesError::NonZeroPadding),
+                Board::<BoardSize5x5>::from_bytes(&bytes)
+            );
+        }
+    }
+
+    #[test]
+    fn zobrist_hash() {
+        let empty = Board::<BoardSize9x9>::new();
+        let mut black = empty;
+        black.set(Pos::from_xy(4, 4), Some(Player::Black));
+        let mut white = empty;
+        white.set(Pos::from_xy(4, 4), Some(Player::White));
+        let mut other_pos = empty;
+        other_pos.set(Pos::from_xy(4, 5), Some(Player::Black));
+
+        assert_eq!(0, empty.zobrist_hash());
+        let hashes: std::collections::HashSet<_> = [empty, black, white, other_pos]
+            .iter()
+            .map(Board::zobrist_hash)
+            .collect();
+        assert_eq!(4, hashes.len());
+    }
+
+    #[test]
+    fn hash_set_of_boards() {
+        let mut black = Board::<BoardSize9x9>::new();
+        black.set(Pos::from_xy(4, 4), Some(Player::Black));
+        let mut white = Board::<BoardSize9x9>::new();
+        white.set(Pos::from_xy(4, 4), Some(Player::White));
+        let mut black_again = Board::<BoardSize9x9>::new();
+        black_again.set(Pos::from_xy(4, 4), Some(Player::Black));
+
+        let boards: std::collections::HashSet<_> =
+            [black, white, black_again].into_iter().collect();
+        assert_eq!(2, boards.len());
+        assert!(boards.contains(&black));
+        assert!(boards.contains(&white));
+    }
+
     #[test]
     fn set_and_get_cells() {
         let mut board = Board::<BoardSize13x13>::new();
@@ -236,11 +909,541 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_many_cells() {
+        let mut board = Board::<BoardSize5x5>::new();
+        board.set(Pos::from_xy(4, 4), Some(Player::White));
+        board.set_many([
+            (Pos::from_xy(0, 0), Some(Player::Black)),
+            (Pos::from_xy(1, 0), Some(Player::White)),
+            (Pos::from_xy(2, 2), Some(Player::White)),
+            (Pos::from_xy(2, 2), Some(Player::Black)),
+            (Pos::from_xy(4, 4), None),
+        ]);
+        assert_eq!(
+            Board::from_str(
+                r#"
+                ○ ● _ _ _
+                _ _ _ _ _
+                _ _ ○ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#
+            )
+            .unwrap(),
+            board
+        );
+    }
+
+    mod flood_fill {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        const BOARD: &str = r#"
+            _ _ ○ _ _
+            _ _ ○ _ _
+            ○ ○ ○ ● _
+            _ _ _ ● ●
+            _ ○ _ _ _
+        "#;
+
+        #[test]
+        fn empty_region() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(
+                vec![
+                    Pos::from_xy(0, 0),
+                    Pos::from_xy(1, 0),
+                    Pos::from_xy(0, 1),
+                    Pos::from_xy(1, 1),
+                ],
+                board.flood_fill(Pos::from_xy(1, 1), |cell| cell.is_none())
+            );
+        }
+
+        #[test]
+        fn single_color_chain() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(
+                vec![
+                    Pos::from_xy(2, 0),
+                    Pos::from_xy(2, 1),
+                    Pos::from_xy(0, 2),
+                    Pos::from_xy(1, 2),
+                    Pos::from_xy(2, 2),
+                ],
+                board.flood_fill(Pos::from_xy(0, 2), |cell| cell == Some(Player::Black))
+            );
+        }
+
+        #[test]
+        fn start_not_matching_predicate() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(
+                Vec::<Pos<BoardSize5x5>>::new(),
+                board.flood_fill(Pos::from_xy(3, 2), |cell| cell == Some(Player::Black))
+            );
+        }
+    }
+
+    mod place_and_capture {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        /// Same position as `Game`'s test for capturing two groups at once
+        #[test]
+        fn capturing_two_groups_at_once() {
+            let white = [(0, 0), (1, 0), (3, 0), (4, 0), (4, 1)];
+            let black = [(0, 1), (1, 1), (2, 1), (3, 1), (4, 2)];
+            let pos = |(x, y)| Pos::<BoardSize5x5>::from_xy(x, y);
+            let mut board = Board::new();
+            board.set_many(white.map(|xy| (pos(xy), Some(Player::White))));
+            board.set_many(black.map(|xy| (pos(xy), Some(Player::Black))));
+
+            let captured = board.place_and_capture(pos((2, 0)), Player::Black).unwrap();
+
+            let mut captured: Vec<_> = captured.iter().copied().collect();
+            captured.sort();
+            assert_eq!(white.map(pos).to_vec(), captured);
+            let mut expected_board = Board::new();
+            expected_board.set_many(black.map(|xy| (pos(xy), Some(Player::Black))));
+            expected_board.set(pos((2, 0)), Some(Player::Black));
+            assert_eq!(expected_board, board);
+        }
+
+        #[test]
+        fn no_capture() {
+            let mut board = Board::<BoardSize5x5>::new();
+            let captured = board
+                .place_and_capture(Pos::from_xy(2, 2), Player::White)
+                .unwrap();
+            assert_eq!(0, captured.len());
+            assert_eq!(Some(Player::White), board[Pos::from_xy(2, 2)]);
+        }
+
+        #[test]
+        fn illegal_moves_leave_the_board_unchanged() {
+            let mut board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ ● _ _ _
+                ● _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+            )
+            .unwrap();
+            let before = board;
+            assert!(matches!(
+                board.place_and_capture(Pos::from_xy(0, 0), Player::Black),
+                Err(PlaceStoneError::Suicide)
+            ));
+            assert!(matches!(
+                board.place_and_capture(Pos::from_xy(1, 0), Player::Black),
+                Err(PlaceStoneError::CellOccupied)
+            ));
+            assert_eq!(before, board);
+            // For White, the point connects to stones that still have liberties
+            assert!(
+                board
+                    .place_and_capture(Pos::from_xy(0, 0), Player::White)
+                    .is_ok()
+            );
+        }
+    }
+
+    mod chains {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn empty_board() {
+            assert_eq!(
+                Vec::<(Player, Vec<Pos<BoardSize5x5>>)>::new(),
+                Board::<BoardSize5x5>::new().chains()
+            );
+        }
+
+        /// Same position as the `more_complicated_board` grouping test
+        #[test]
+        fn more_complicated_board() {
+            let board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ ● _ ○ ○
+                ● ● ○ _ _
+                _ ○ ○ ● _
+                ○ _ _ ● _
+                _ _ _ _ ○
+            "#,
+            )
+            .unwrap();
+            let chains = board.chains();
+
+            assert_eq!(6, chains.len());
+            assert_eq!(
+                (
+                    Player::Black,
+                    vec![Pos::from_xy(2, 1), Pos::from_xy(1, 2), Pos::from_xy(2, 2)]
+                ),
+                chains[2]
+            );
+            let num_stones: usize = chains.iter().map(|(_, stones)| stones.len()).sum();
+            assert_eq!(board.occupied().count(), num_stones);
+        }
+    }
+
+    mod equals_ignoring {
+        use crate::board::BoardSize5x5;
+
+        use super::*;
+
+        const BOARD: &str = r#"
+            _ ○ ● _ _
+            ○ ● _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#;
+
+        #[test]
+        fn differences_only_at_ignored_points() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            let mut other = board;
+            other.set(Pos::from_xy(4, 4), Some(Player::Black));
+            other.set(Pos::from_xy(1, 0), None);
+            other.set(Pos::from_xy(2, 0), Some(Player::Black));
+            let ignore = [Pos::from_xy(4, 4), Pos::from_xy(1, 0), Pos::from_xy(2, 0)];
+            assert!(board.equals_ignoring(&other, &ignore));
+            assert!(other.equals_ignoring(&board, &ignore));
+        }
+
+        #[test]
+        fn differences_outside_of_ignored_points() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            let mut other = board;
+            other.set(Pos::from_xy(4, 4), Some(Player::Black));
+            other.set(Pos::from_xy(1, 1), Some(Player::Black));
+            assert!(!board.equals_ignoring(&other, &[Pos::from_xy(4, 4)]));
+            assert!(!board.equals_ignoring(&other, &[]));
+            assert!(board.equals_ignoring(&board, &[]));
+        }
+    }
+
+    mod adjacent_empty_count {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        const BOARD: &str = r#"
+            ○ _ _ ● _
+            ● _ _ _ _
+            _ _ ○ ● _
+            _ _ _ _ ○
+            _ _ _ ○ ●
+        "#;
+
+        #[test]
+        fn corner() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(1, board.adjacent_empty_count(Pos::from_xy(0, 0)));
+            assert_eq!(0, board.adjacent_empty_count(Pos::from_xy(4, 4)));
+            assert_eq!(2, board.adjacent_empty_count(Pos::from_xy(0, 4)));
+        }
+
+        #[test]
+        fn edge() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(3, board.adjacent_empty_count(Pos::from_xy(3, 0)));
+            assert_eq!(2, board.adjacent_empty_count(Pos::from_xy(0, 1)));
+            assert_eq!(2, board.adjacent_empty_count(Pos::from_xy(4, 3)));
+        }
+
+        #[test]
+        fn center() {
+            let board = Board::<BoardSize5x5>::from_str(BOARD).unwrap();
+            assert_eq!(3, board.adjacent_empty_count(Pos::from_xy(2, 2)));
+            assert_eq!(3, board.adjacent_empty_count(Pos::from_xy(3, 2)));
+            assert_eq!(3, board.adjacent_empty_count(Pos::from_xy(1, 2)));
+        }
+
+        #[test]
+        fn empty_board() {
+            let board = Board::<BoardSize5x5>::new();
+            assert_eq!(2, board.adjacent_empty_count(Pos::from_xy(0, 0)));
+            assert_eq!(3, board.adjacent_empty_count(Pos::from_xy(2, 0)));
+            assert_eq!(4, board.adjacent_empty_count(Pos::from_xy(2, 2)));
+        }
+    }
+
+    mod is_eye {
+        use crate::board::BoardSize5x5;
+
+        use super::*;
+
+        #[test]
+        fn real_eyes() {
+            let board = Board::<BoardSize5x5>::from_str(
+                r#"
+                _ ○ _ ○ _
+                ○ ○ ○ ○ ○
+                ○ _ ○ _ _
+                ○ ○ ○ _ _
+                _ _ _ _ _
+                "#,
+            )
+            .unwrap();
+            assert!(board.is_eye(Pos::from_xy(0, 0), Player::Black));
+            assert!(board.is_eye(Pos::from_xy(2, 0), Player::Black));
+            assert!(board.is_eye(Pos::from_xy(1, 2), Player::Black));
+            assert!(!board.is_eye(Pos::from_xy(1, 2), Player::White));
+            assert!(!board.is_eye(Pos::from_xy(3, 2), Player::Black));
+            assert!(!board.is_eye(Pos::from_xy(1, 1), Player::Black));
+        }
+
+        #[test]
+        fn false_eyes() {
+            let board = Board::<BoardSize5x5>::from_str(
+                r#"
+                ● ○ _ ○ _
+                ○ _ ○ ● _
+                ● ○ ● ○ _
+                _ _ _ _ _
+                _ _ _ _ _
+                "#,
+            )
+            .unwrap();
+            // Edge point with an opponent diagonal
+            assert!(!board.is_eye(Pos::from_xy(2, 0), Player::Black));
+            // Center point with more than one opponent diagonal
+            assert!(!board.is_eye(Pos::from_xy(1, 1), Player::Black));
+        }
+    }
+
+    mod parse_with_alternative_glyphs {
+        use crate::board::BoardSize5x5;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        const EXPECTED: &str = r#"
+            _ ○ ● _ _
+            ○ ● _ _ _
+            _ _ _ _ _
+            _ _ _ ○ _
+            _ _ _ _ ●
+        "#;
+
+        #[test]
+        fn same_position_with_different_glyphs() {
+            let expected = Board::<BoardSize5x5>::from_str(EXPECTED).unwrap();
+            for input in [
+                r#"
+                _ ● ○ _ _
+                ● ○ _ _ _
+                _ _ _ _ _
+                _ _ _ ● _
+                _ _ _ _ ○
+                "#,
+                r#"
+                . X O . .
+                X O . . .
+                . . . . .
+                . . . X .
+                . . . . O
+                "#,
+                r#"
+                + x o + +
+                x o + + +
+                + + + + +
+                + + + x +
+                + + + + o
+                "#,
+                r#"
+                _ B W _ _
+                B W _ _ _
+                _ _ _ _ _
+                _ _ _ B _
+                _ _ _ _ W
+                "#,
+                r#"
+                . # O . .
+                # O . . .
+                . . . . .
+                . . . # .
+                . . . . O
+                "#,
+            ] {
+                assert_eq!(expected, Board::<BoardSize5x5>::parse(input).unwrap());
+            }
+        }
+
+        #[test]
+        fn glyphs_can_be_mixed() {
+            assert_eq!(
+                Board::<BoardSize5x5>::from_str(EXPECTED).unwrap(),
+                Board::<BoardSize5x5>::parse(
+                    r#"
+                    . ● W _ +
+                    X ○ . . .
+                    _ _ _ _ _
+                    . . . # .
+                    . . . . o
+                    "#
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn invalid_glyph() {
+            assert_eq!(
+                Err(BoardParseError::InvalidChar {
+                    found: 'Y',
+                    pos: (1, 0)
+                }),
+                Board::<BoardSize5x5>::parse(
+                    r#"
+                    . Y . . .
+                    . . . . .
+                    . . . . .
+                    . . . . .
+                    . . . . .
+                    "#
+                )
+            );
+        }
+
+        #[test]
+        fn reads_back_to_ascii() {
+            let board = Board::<BoardSize5x5>::from_str(EXPECTED).unwrap();
+            for style in [StoneStyle::UNICODE, StoneStyle::ASCII] {
+                assert_eq!(
+                    board,
+                    Board::<BoardSize5x5>::parse(&board.to_ascii(&style)).unwrap()
+                );
+            }
+        }
+
+        #[test]
+        fn from_str_rejects_alternative_glyphs() {
+            assert!(
+                Board::<BoardSize5x5>::from_str(
+                    r#"
+                . X O . .
+                X O . . .
+                . . . . .
+                . . . X .
+                . . . . O
+                "#
+                )
+                .is_err()
+            );
+        }
+    }
+
     mod parse_board_from_string {
         use crate::board::BoardSize3x3;
+        use pretty_assertions::assert_eq;
 
         use super::*;
 
+        #[test]
+        fn test_parse_labeled_board() {
+            let labeled = r#"
+                   A B C D E F G H J
+                 9 _ _ _ _ _ _ _ _ _ 9
+                 8 _ _ _ _ _ _ _ _ _ 8
+                 7 _ _ ○ _ _ _ ● _ _ 7
+                 6 _ _ _ _ _ _ _ _ _ 6
+                 5 _ _ _ _ ○ _ _ _ _ 5
+                 4 _ _ _ _ _ _ _ _ _ 4
+                 3 _ _ ● _ _ _ ○ _ _ 3
+                 2 _ _ _ _ _ _ _ _ _ 2
+                 1 _ _ _ _ _ _ _ _ ● 1
+                   A B C D E F G H J
+            "#;
+            let unlabeled = r#"
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ ○ _ _ _ ● _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ ○ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ ● _ _ _ ○ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ ●
+            "#;
+            assert_eq!(
+                Board::<BoardSize9x9>::from_str(unlabeled).unwrap(),
+                Board::<BoardSize9x9>::from_labeled_str(labeled).unwrap()
+            );
+            assert_eq!(
+                Board::<BoardSize9x9>::from_str(unlabeled).unwrap(),
+                Board::<BoardSize9x9>::from_labeled_str(unlabeled).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_parse_labeled_board_with_wrong_size() {
+            let labeled = r#"
+                  A B C
+                3 _ ○ _
+                2 ○ _ ○
+            "#;
+            assert_eq!(
+                Err(BoardParseError::TooFewCells),
+                Board::<BoardSize3x3>::from_labeled_str(labeled)
+            );
+        }
+
+        #[test]
+        fn test_parse_invalid_char() {
+            let input = r#"
+                _ ○ ○
+                ○ X ●
+                ○ _ ○
+            "#;
+            assert_eq!(
+                Err(BoardParseError::InvalidChar {
+                    found: 'X',
+                    pos: (1, 1)
+                }),
+                Board::<BoardSize3x3>::from_str(input)
+            );
+        }
+
+        #[test]
+        fn test_parse_too_few_cells() {
+            assert_eq!(
+                Err(BoardParseError::TooFewCells),
+                Board::<BoardSize3x3>::from_str("_ ○ ○ ○ ● ● ○ _")
+            );
+            assert_eq!(
+                Err(BoardParseError::TooFewCells),
+                Board::<BoardSize3x3>::from_str("")
+            );
+        }
+
+        #[test]
+        fn test_parse_extra_characters() {
+            let input = r#"
+                _ ○ ○
+                ○ ● ●
+                ○ _ ○ ●
+            "#;
+            assert_eq!(
+                Err(BoardParseError::ExtraCharacters),
+                Board::<BoardSize3x3>::from_str(input)
+            );
+        }
+
         #[test]
         fn test_parse_valid_board() {
             let input = r#"
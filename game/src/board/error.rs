@@ -4,4 +4,63 @@ use derive_more::{Display, Error};
 pub enum PlaceStoneError {
     #[display("Location already occupied")]
     CellOccupied,
+
+    #[display("Move would leave its own group without liberties")]
+    Suicide,
+
+    #[display("Move would immediately retake a ko")]
+    Ko,
+
+    #[display("Move would repeat an earlier board position")]
+    Superko,
+
+    #[display("The game is already over")]
+    GameOver,
+
+    #[display("Move {x}/{y} is outside of the board")]
+    OutOfBounds { x: usize, y: usize },
+}
+
+#[derive(Error, Display, Debug, Clone, PartialEq, Eq)]
+pub enum BoardParseError {
+    #[display("Invalid character {found:?} at {pos:?}: not a stone or an empty cell")]
+    InvalidChar { found: char, pos: (usize, usize) },
+
+    #[display("Input ended before all cells of the board were given")]
+    TooFewCells,
+
+    #[display("Extra characters found after the board")]
+    ExtraCharacters,
+}
+
+#[derive(Error, Display, Debug, Clone, PartialEq, Eq)]
+pub enum BoardBytesError {
+    #[display("Expected {expected} bytes but got {actual}")]
+    WrongLength { expected: usize, actual: usize },
+
+    #[display("Cell at {pos:?} has a color but isn't occupied")]
+    InvalidCell { pos: (usize, usize) },
+
+    #[display("Unused bits after the last cell must be zero")]
+    NonZeroPadding,
+}
+
+#[derive(Error, Display, Debug, Clone, PartialEq, Eq)]
+pub enum GtpVertexError {
+    #[display(
+        "GTP vertex must be a column letter other than I and a row number but was {vertex:?}"
+    )]
+    InvalidFormat { vertex: String },
+
+    #[display("GTP vertex {vertex:?} is outside of the board")]
+    OutOfBounds { vertex: String },
+}
+
+#[derive(Error, Display, Debug, Clone, PartialEq, Eq)]
+pub enum SgfCoordError {
+    #[display("SGF coordinate must be two lowercase letters but was {coord:?}")]
+    InvalidFormat { coord: String },
+
+    #[display("SGF coordinate {coord:?} is outside of the board")]
+    OutOfBounds { coord: String },
 }
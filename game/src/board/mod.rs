@@ -2,11 +2,17 @@ mod board;
 mod error;
 mod player;
 mod pos;
+mod style;
+#[cfg(feature = "svg")]
+mod svg;
+mod zobrist;
 
 pub use board::Board;
-pub use error::PlaceStoneError;
+pub use error::{BoardBytesError, BoardParseError, GtpVertexError, PlaceStoneError, SgfCoordError};
 pub use player::Player;
 pub use pos::{
     BoardSize, BoardSize3x3, BoardSize5x5, BoardSize7x7, BoardSize9x9, BoardSize13x13,
-    BoardSize19x19, NumStones, Pos,
+    BoardSize19x19, BoardSize21x21, Direction, Notation, NumStones, Origin, Pos, PosDisplay,
 };
+pub use style::StoneStyle;
+pub(crate) use zobrist::stone_key;
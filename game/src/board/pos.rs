@@ -2,54 +2,83 @@ use derive_more::{Debug, Display};
 use derive_where::derive_where;
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-use crate::utils::IntType;
+use crate::{
+    board::{GtpVertexError, SgfCoordError},
+    utils::IntType,
+};
 
 pub trait BoardSize {
     const SIZE: usize;
     type Index: IntType;
-}
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize3x3;
-impl BoardSize for BoardSize3x3 {
-    const SIZE: usize = 3;
-    type Index = u8;
+    /// The conventional star points (hoshi) as `(x, y)`, ordered row by row.
+    /// Empty for boards too small to have them.
+    fn star_points() -> &'static [(usize, usize)] {
+        match Self::SIZE {
+            9 => &[(2, 2), (6, 2), (4, 4), (2, 6), (6, 6)],
+            13 => &[(3, 3), (9, 3), (6, 6), (3, 9), (9, 9)],
+            19 => &[
+                (3, 3),
+                (9, 3),
+                (15, 3),
+                (3, 9),
+                (9, 9),
+                (15, 9),
+                (3, 15),
+                (9, 15),
+                (15, 15),
+            ],
+            21 => &[
+                (3, 3),
+                (10, 3),
+                (17, 3),
+                (3, 10),
+                (10, 10),
+                (17, 10),
+                (3, 17),
+                (10, 17),
+                (17, 17),
+            ],
+            _ => &[],
+        }
+    }
 }
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize5x5;
-impl BoardSize for BoardSize5x5 {
-    const SIZE: usize = 5;
-    type Index = u8;
+/// Picks the smallest [IntType] that can count all stones on a board, so [NumStones] and [Pos]
+/// stay small. `FITS_IN_U8` is whether `SIZE * SIZE` fits in a `u8`.
+pub struct IndexSelector<const FITS_IN_U8: bool>;
+
+pub trait SelectIndex {
+    type Index: IntType;
 }
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize7x7;
-impl BoardSize for BoardSize7x7 {
-    const SIZE: usize = 7;
+impl SelectIndex for IndexSelector<true> {
     type Index = u8;
 }
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize9x9;
-impl BoardSize for BoardSize9x9 {
-    const SIZE: usize = 9;
-    type Index = u8; // Using u8 for 9x9 board, since 9*9=81 fits in u8
+impl SelectIndex for IndexSelector<false> {
+    type Index = u16;
 }
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize13x13;
-impl BoardSize for BoardSize13x13 {
-    const SIZE: usize = 13;
-    type Index = u8; // Using u8 for 13x13 board, since 13*13=169 fits in u8
+macro_rules! board_size {
+    ($name:ident, $size:literal) => {
+        #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name;
+        impl BoardSize for $name {
+            const SIZE: usize = $size;
+            type Index =
+                <IndexSelector<{ $size * $size <= u8::MAX as usize }> as SelectIndex>::Index;
+        }
+    };
 }
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoardSize19x19;
-impl BoardSize for BoardSize19x19 {
-    const SIZE: usize = 19;
-    type Index = u16; // Using u8 for 19x19 board, since 19*19=361 doesn't fit in u8
-}
+board_size!(BoardSize3x3, 3);
+board_size!(BoardSize5x5, 5);
+board_size!(BoardSize7x7, 7);
+board_size!(BoardSize9x9, 9);
+board_size!(BoardSize13x13, 13);
+board_size!(BoardSize19x19, 19);
+board_size!(BoardSize21x21, 21);
 
 #[derive_where(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NumStones<BS: BoardSize> {
@@ -120,6 +149,61 @@ impl<BS: BoardSize> SubAssign for NumStones<BS> {
     }
 }
 
+impl<BS: BoardSize> Add<usize> for NumStones<BS> {
+    type Output = Self;
+
+    fn add(self, other: usize) -> Self::Output {
+        self + Self::from_usize(other)
+    }
+}
+
+impl<BS: BoardSize> AddAssign<usize> for NumStones<BS> {
+    fn add_assign(&mut self, other: usize) {
+        *self += Self::from_usize(other);
+    }
+}
+
+impl<BS: BoardSize> Sub<usize> for NumStones<BS> {
+    type Output = Self;
+
+    fn sub(self, other: usize) -> Self::Output {
+        self - Self::from_usize(other)
+    }
+}
+
+impl<BS: BoardSize> SubAssign<usize> for NumStones<BS> {
+    fn sub_assign(&mut self, other: usize) {
+        *self -= Self::from_usize(other);
+    }
+}
+
+/// One of the four orthogonal directions on the board. `Up` is towards `y == 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Left,
+    Right,
+    Down,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Right,
+        Direction::Down,
+    ];
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
 #[derive_where(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Pos<BS: BoardSize> {
     index: NumStones<BS>,
@@ -200,14 +284,193 @@ impl<BS: BoardSize> Pos<BS> {
         }
     }
 
+    /// Parses SGF coordinates like `"cd"`, where the first letter is the column and the second the row,
+    /// both counting from `a` in the top left.
+    pub fn from_sgf_coord(coord: &str) -> Result<Self, SgfCoordError> {
+        let &[x, y] = coord.as_bytes() else {
+            return Err(SgfCoordError::InvalidFormat {
+                coord: coord.to_string(),
+            });
+        };
+        if !x.is_ascii_lowercase() || !y.is_ascii_lowercase() {
+            return Err(SgfCoordError::InvalidFormat {
+                coord: coord.to_string(),
+            });
+        }
+        let (x, y) = (usize::from(x - b'a'), usize::from(y - b'a'));
+        if x >= <BS as BoardSize>::SIZE || y >= <BS as BoardSize>::SIZE {
+            return Err(SgfCoordError::OutOfBounds {
+                coord: coord.to_string(),
+            });
+        }
+        Ok(Self::from_xy(x, y))
+    }
+
+    /// Parses GTP vertices like `"D4"`: a column letter from `A`, skipping `I`, and a row number from 1
+    /// at the bottom. Letters are case-insensitive. This is the inverse of [Origin::BottomLeft] with
+    /// [Notation::Letters]. Passes aren't vertices, they need to be handled before.
+    pub fn from_gtp_vertex(vertex: &str) -> Result<Self, GtpVertexError> {
+        let invalid_format = || GtpVertexError::InvalidFormat {
+            vertex: vertex.to_string(),
+        };
+        let mut chars = vertex.chars();
+        let column = chars
+            .next()
+            .filter(|column| column.is_ascii_alphabetic())
+            .map(|column| column.to_ascii_uppercase())
+            .ok_or_else(invalid_format)?;
+        let row = chars.as_str();
+        if column == 'I' || row.is_empty() || !row.bytes().all(|digit| digit.is_ascii_digit()) {
+            return Err(invalid_format());
+        }
+        let x = if column < 'I' {
+            usize::from(column as u8 - b'A')
+        } else {
+            usize::from(column as u8 - b'B')
+        };
+        match row.parse::<usize>() {
+            Ok(row)
+                if (1..=<BS as BoardSize>::SIZE).contains(&row) && x < <BS as BoardSize>::SIZE =>
+            {
+                Ok(Self::from_xy(x, <BS as BoardSize>::SIZE - row))
+            }
+            _ => Err(GtpVertexError::OutOfBounds {
+                vertex: vertex.to_string(),
+            }),
+        }
+    }
+
+    /// Inverse of [Self::from_sgf_coord]
+    pub fn to_sgf_coord(&self) -> String {
+        [self.x(), self.y()]
+            .into_iter()
+            .map(|coord| char::from(b'a' + coord as u8))
+            .collect()
+    }
+
+    /// The neighbor in direction `dir`, or `None` if `self` is on that edge of the board
+    pub fn step(&self, dir: Direction) -> Option<Self> {
+        match dir {
+            Direction::Up => self.up(),
+            Direction::Left => self.left(),
+            Direction::Right => self.right(),
+            Direction::Down => self.down(),
+        }
+    }
+
+    /// The position `n` steps in direction `dir`, or `None` if that is off the board
+    pub fn step_by(&self, dir: Direction, n: usize) -> Option<Self> {
+        let (x, y) = (self.x(), self.y());
+        let (x, y) = match dir {
+            Direction::Up => (Some(x), y.checked_sub(n)),
+            Direction::Left => (x.checked_sub(n), Some(y)),
+            Direction::Right => (x.checked_add(n), Some(y)),
+            Direction::Down => (Some(x), y.checked_add(n)),
+        };
+        match (x, y) {
+            (Some(x), Some(y)) if x < <BS as BoardSize>::SIZE && y < <BS as BoardSize>::SIZE => {
+                Some(Self::from_xy(x, y))
+            }
+            _ => None,
+        }
+    }
+
+    /// The orthogonal neighbors that are on the board
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> + use<BS> {
+        let pos = *self;
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| pos.step(dir))
+    }
+
+    /// Number of orthogonal steps from `self` to `other`
+    pub fn manhattan_distance(&self, other: Self) -> usize {
+        self.x().abs_diff(other.x()) + self.y().abs_diff(other.y())
+    }
+
+    /// Number of steps from `self` to `other` if diagonal steps are allowed
+    pub fn chebyshev_distance(&self, other: Self) -> usize {
+        self.x()
+            .abs_diff(other.x())
+            .max(self.y().abs_diff(other.y()))
+    }
+
     pub fn all_positions() -> impl Iterator<Item = Self> + ExactSizeIterator {
         (0..<BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE).map(Self::_from_index)
     }
+
+    pub fn is_star_point(&self) -> bool {
+        BS::star_points().contains(&(self.x(), self.y()))
+    }
+}
+
+/// The corner of the board that coordinates count from, see [Pos::display_with]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Origin {
+    /// Like [Pos::x] and [Pos::y], rows count down from the top
+    #[default]
+    TopLeft,
+    /// Like most Go diagrams, rows count up from the bottom
+    BottomLeft,
+}
+
+/// How coordinates are written, see [Pos::display_with]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// Zero-based column and row separated by a slash, e.g. `3/15`
+    #[default]
+    Numeric,
+    /// Column letter and one-based row, e.g. `D4`. Columns skip the letter `I` to avoid confusion with `J`.
+    Letters,
+}
+
+impl<BS: BoardSize> Pos<BS> {
+    /// Formats the position counting from `origin`, written in `notation`.
+    /// The [std::fmt::Display] of [Pos] is the same as [Origin::TopLeft] with [Notation::Numeric].
+    pub fn display_with(&self, origin: Origin, notation: Notation) -> PosDisplay<BS> {
+        PosDisplay {
+            pos: *self,
+            origin,
+            notation,
+        }
+    }
+}
+
+/// A [Pos] formatted with [Pos::display_with]
+pub struct PosDisplay<BS: BoardSize> {
+    pos: Pos<BS>,
+    origin: Origin,
+    notation: Notation,
+}
+
+impl<BS: BoardSize> std::fmt::Display for PosDisplay<BS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let x = self.pos.x();
+        let y = match self.origin {
+            Origin::TopLeft => self.pos.y(),
+            Origin::BottomLeft => <BS as BoardSize>::SIZE - 1 - self.pos.y(),
+        };
+        match self.notation {
+            Notation::Numeric => write!(f, "{x}/{y}"),
+            Notation::Letters => {
+                let column = if x < 8 {
+                    b'A' + x as u8
+                } else {
+                    b'B' + x as u8
+                };
+                write!(f, "{}{}", char::from(column), y + 1)
+            }
+        }
+    }
 }
 
 impl<BS: BoardSize> std::fmt::Display for Pos<BS> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}", self.x(), self.y())
+        write!(
+            f,
+            "{}",
+            self.display_with(Origin::default(), Notation::default())
+        )
     }
 }
 
@@ -216,3 +479,244 @@ impl<BS: BoardSize> std::fmt::Debug for Pos<BS> {
         write!(f, "{}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn num_stones_arithmetic() {
+        let mut num = NumStones::<BoardSize9x9>::from_usize(3);
+        assert_eq!(NumStones::from_usize(5), num + 2);
+        assert_eq!(NumStones::from_usize(2), num - 1);
+        num += 1;
+        assert_eq!(NumStones::from_usize(4), num);
+        num -= 2;
+        assert_eq!(NumStones::from_usize(2), num);
+        num += NumStones::ONE;
+        num -= 1;
+        assert_eq!(NumStones::from_usize(2), num + NumStones::ZERO - 0);
+    }
+
+    #[test]
+    fn display_with_origin_and_notation() {
+        let pos = Pos::<BoardSize19x19>::from_xy(3, 15);
+        assert_eq!(
+            "3/15",
+            pos.display_with(Origin::TopLeft, Notation::Numeric)
+                .to_string()
+        );
+        assert_eq!(
+            "3/3",
+            pos.display_with(Origin::BottomLeft, Notation::Numeric)
+                .to_string()
+        );
+        assert_eq!(
+            "D16",
+            pos.display_with(Origin::TopLeft, Notation::Letters)
+                .to_string()
+        );
+        assert_eq!(
+            "D4",
+            pos.display_with(Origin::BottomLeft, Notation::Letters)
+                .to_string()
+        );
+        assert_eq!("3/15", pos.to_string());
+    }
+
+    #[test]
+    fn letters_skip_i() {
+        let display = |x, y| {
+            Pos::<BoardSize19x19>::from_xy(x, y)
+                .display_with(Origin::BottomLeft, Notation::Letters)
+                .to_string()
+        };
+        assert_eq!("A19", display(0, 0));
+        assert_eq!("H1", display(7, 18));
+        assert_eq!("J1", display(8, 18));
+        assert_eq!("T10", display(18, 9));
+    }
+
+    #[test]
+    fn sgf_coords() {
+        assert_eq!(
+            Pos::<BoardSize19x19>::from_xy(0, 0),
+            Pos::from_sgf_coord("aa").unwrap()
+        );
+        assert_eq!(
+            Pos::<BoardSize19x19>::from_xy(18, 18),
+            Pos::from_sgf_coord("ss").unwrap()
+        );
+        assert_eq!(
+            Pos::<BoardSize19x19>::from_xy(2, 3),
+            Pos::from_sgf_coord("cd").unwrap()
+        );
+        for pos in Pos::<BoardSize19x19>::all_positions() {
+            assert_eq!(pos, Pos::from_sgf_coord(&pos.to_sgf_coord()).unwrap());
+        }
+        assert_eq!("ss", Pos::<BoardSize19x19>::from_xy(18, 18).to_sgf_coord());
+    }
+
+    #[test]
+    fn gtp_vertices() {
+        assert_eq!(
+            Pos::<BoardSize19x19>::from_xy(0, 18),
+            Pos::from_gtp_vertex("A1").unwrap()
+        );
+        assert_eq!(
+            Pos::<BoardSize19x19>::from_xy(8, 0),
+            Pos::from_gtp_vertex("j19").unwrap()
+        );
+        assert_eq!(
+            Pos::<BoardSize9x9>::from_xy(3, 5),
+            Pos::from_gtp_vertex("D4").unwrap()
+        );
+        for pos in Pos::<BoardSize19x19>::all_positions() {
+            let vertex = pos
+                .display_with(Origin::BottomLeft, Notation::Letters)
+                .to_string();
+            assert_eq!(pos, Pos::from_gtp_vertex(&vertex).unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_gtp_vertices() {
+        for vertex in ["", "A", "1", "I5", "i5", "A-1", "A1x", "pass", "é1"] {
+            assert_eq!(
+                Err(GtpVertexError::InvalidFormat {
+                    vertex: vertex.to_string()
+                }),
+                Pos::<BoardSize19x19>::from_gtp_vertex(vertex)
+            );
+        }
+        for vertex in ["A0", "A20", "U1", "Z5", "A99999999999999999999999"] {
+            assert_eq!(
+                Err(GtpVertexError::OutOfBounds {
+                    vertex: vertex.to_string()
+                }),
+                Pos::<BoardSize19x19>::from_gtp_vertex(vertex)
+            );
+        }
+        assert!(Pos::<BoardSize9x9>::from_gtp_vertex("K1").is_err());
+    }
+
+    #[test]
+    fn invalid_sgf_coords() {
+        for coord in ["", "a", "abc", "AA", "a1", "é"] {
+            assert_eq!(
+                Err(SgfCoordError::InvalidFormat {
+                    coord: coord.to_string()
+                }),
+                Pos::<BoardSize19x19>::from_sgf_coord(coord)
+            );
+        }
+        assert_eq!(
+            Err(SgfCoordError::OutOfBounds {
+                coord: "at".to_string()
+            }),
+            Pos::<BoardSize19x19>::from_sgf_coord("at")
+        );
+        assert!(Pos::<BoardSize19x19>::from_sgf_coord("jj").is_ok());
+        assert!(Pos::<BoardSize9x9>::from_sgf_coord("jj").is_err());
+    }
+
+    #[test]
+    fn step_in_each_direction() {
+        let pos = Pos::<BoardSize5x5>::from_xy(2, 3);
+        assert_eq!(Some(Pos::from_xy(2, 2)), pos.step(Direction::Up));
+        assert_eq!(Some(Pos::from_xy(1, 3)), pos.step(Direction::Left));
+        assert_eq!(Some(Pos::from_xy(3, 3)), pos.step(Direction::Right));
+        assert_eq!(Some(Pos::from_xy(2, 4)), pos.step(Direction::Down));
+        for dir in Direction::ALL {
+            assert_eq!(Some(pos), pos.step(dir).unwrap().step(dir.opposite()));
+        }
+    }
+
+    #[test]
+    fn step_off_each_edge() {
+        let top_left = Pos::<BoardSize5x5>::from_xy(0, 0);
+        let bottom_right = Pos::<BoardSize5x5>::from_xy(4, 4);
+        assert_eq!(None, top_left.step(Direction::Up));
+        assert_eq!(None, top_left.step(Direction::Left));
+        assert_eq!(None, bottom_right.step(Direction::Right));
+        assert_eq!(None, bottom_right.step(Direction::Down));
+        assert_eq!(2, top_left.neighbors().count());
+    }
+
+    #[test]
+    fn step_by() {
+        let pos = Pos::<BoardSize5x5>::from_xy(1, 3);
+        assert_eq!(Some(pos), pos.step_by(Direction::Up, 0));
+        assert_eq!(Some(Pos::from_xy(1, 0)), pos.step_by(Direction::Up, 3));
+        assert_eq!(None, pos.step_by(Direction::Up, 4));
+        assert_eq!(Some(Pos::from_xy(0, 3)), pos.step_by(Direction::Left, 1));
+        assert_eq!(None, pos.step_by(Direction::Left, 2));
+        assert_eq!(Some(Pos::from_xy(4, 3)), pos.step_by(Direction::Right, 3));
+        assert_eq!(None, pos.step_by(Direction::Right, 4));
+        assert_eq!(Some(Pos::from_xy(1, 4)), pos.step_by(Direction::Down, 1));
+        assert_eq!(None, pos.step_by(Direction::Down, 2));
+        assert_eq!(None, pos.step_by(Direction::Down, usize::MAX));
+    }
+
+    #[test]
+    #[should_panic]
+    fn num_stones_overflow() {
+        let _ = NumStones::<BoardSize9x9>::from_usize(200) + 300;
+    }
+
+    #[test]
+    fn star_points_9x9() {
+        assert_eq!(
+            &[(2, 2), (6, 2), (4, 4), (2, 6), (6, 6)],
+            BoardSize9x9::star_points()
+        );
+        assert!(Pos::<BoardSize9x9>::from_xy(4, 4).is_star_point());
+        assert!(!Pos::<BoardSize9x9>::from_xy(3, 3).is_star_point());
+    }
+
+    #[test]
+    fn star_points_13x13() {
+        assert_eq!(
+            &[(3, 3), (9, 3), (6, 6), (3, 9), (9, 9)],
+            BoardSize13x13::star_points()
+        );
+        assert!(Pos::<BoardSize13x13>::from_xy(9, 3).is_star_point());
+        assert!(!Pos::<BoardSize13x13>::from_xy(2, 2).is_star_point());
+    }
+
+    #[test]
+    fn star_points_19x19() {
+        let star_points = BoardSize19x19::star_points();
+        assert_eq!(9, star_points.len());
+        for x in [3, 9, 15] {
+            for y in [3, 9, 15] {
+                assert!(star_points.contains(&(x, y)));
+                assert!(Pos::<BoardSize19x19>::from_xy(x, y).is_star_point());
+            }
+        }
+        assert_eq!(
+            9,
+            Pos::<BoardSize19x19>::all_positions()
+                .filter(Pos::is_star_point)
+                .count()
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let pos = |x, y| Pos::<BoardSize9x9>::from_xy(x, y);
+        assert_eq!(0, pos(4, 4).manhattan_distance(pos(4, 4)));
+        assert_eq!(0, pos(4, 4).chebyshev_distance(pos(4, 4)));
+        assert_eq!(1, pos(4, 4).manhattan_distance(pos(4, 5)));
+        assert_eq!(1, pos(4, 4).chebyshev_distance(pos(3, 4)));
+        assert_eq!(2, pos(4, 4).manhattan_distance(pos(5, 3)));
+        assert_eq!(1, pos(4, 4).chebyshev_distance(pos(5, 3)));
+        assert_eq!(11, pos(8, 0).manhattan_distance(pos(1, 4)));
+        assert_eq!(7, pos(1, 4).chebyshev_distance(pos(8, 0)));
+    }
+
+    #[test]
+    fn small_boards_have_no_star_points() {
+        assert!(BoardSize5x5::star_points().is_empty());
+    }
+}
@@ -0,0 +1,37 @@
+use super::Player;
+
+/// Glyphs used to draw a board, so different frontends can share the same rendering code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoneStyle {
+    pub black: &'static str,
+    pub white: &'static str,
+    pub empty: &'static str,
+}
+
+impl StoneStyle {
+    pub const UNICODE: Self = Self {
+        black: "●",
+        white: "○",
+        empty: "_",
+    };
+
+    pub const ASCII: Self = Self {
+        black: "X",
+        white: "O",
+        empty: ".",
+    };
+
+    pub fn glyph(&self, cell: Option<Player>) -> &'static str {
+        match cell {
+            Some(Player::Black) => self.black,
+            Some(Player::White) => self.white,
+            None => self.empty,
+        }
+    }
+}
+
+impl Default for StoneStyle {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
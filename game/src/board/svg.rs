@@ -0,0 +1,116 @@
+use std::fmt::Write;
+
+use super::{Board, BoardSize, Player, Pos};
+
+/// Distance between two grid lines, in pixels
+const CELL_SIZE: usize = 30;
+const STONE_RADIUS: usize = CELL_SIZE * 9 / 20;
+const STAR_POINT_RADIUS: usize = 3;
+
+impl<BS: BoardSize> Board<BS>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    /// Renders the board as a standalone SVG document with grid lines, star points and stones,
+    /// e.g. to embed diagrams in web pages.
+    pub fn to_svg(&self) -> String {
+        let size = <BS as BoardSize>::SIZE;
+        // Leave a margin of one cell around the grid
+        let width = CELL_SIZE * (size + 1);
+        let coord = |index: usize| CELL_SIZE * (index + 1);
+        let first = coord(0);
+        let last = coord(size - 1);
+
+        let mut svg = String::new();
+        // Writing to a String can't fail
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{width}" viewBox="0 0 {width} {width}">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r##"  <rect width="{width}" height="{width}" fill="#dcb35c"/>"##
+        )
+        .unwrap();
+        for index in 0..size {
+            let pos = coord(index);
+            writeln!(
+                svg,
+                r#"  <line x1="{first}" y1="{pos}" x2="{last}" y2="{pos}" stroke="black"/>"#
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"  <line x1="{pos}" y1="{first}" x2="{pos}" y2="{last}" stroke="black"/>"#
+            )
+            .unwrap();
+        }
+        for (x, y) in BS::star_points() {
+            let (cx, cy) = (coord(*x), coord(*y));
+            writeln!(
+                svg,
+                r#"  <circle class="star" cx="{cx}" cy="{cy}" r="{STAR_POINT_RADIUS}" fill="black"/>"#
+            )
+            .unwrap();
+        }
+        for pos in Pos::<BS>::all_positions() {
+            let (cx, cy) = (coord(pos.x()), coord(pos.y()));
+            match self[pos] {
+                Some(Player::Black) => writeln!(
+                    svg,
+                    r#"  <circle class="stone black" cx="{cx}" cy="{cy}" r="{STONE_RADIUS}" fill="black"/>"#
+                ),
+                Some(Player::White) => writeln!(
+                    svg,
+                    r#"  <circle class="stone white" cx="{cx}" cy="{cy}" r="{STONE_RADIUS}" fill="white" stroke="black"/>"#
+                ),
+                None => Ok(()),
+            }
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{BoardSize5x5, BoardSize9x9};
+
+    use super::*;
+
+    #[test]
+    fn stones_are_drawn() {
+        let board = Board::<BoardSize9x9>::from_str(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ ○ _ _ _ ● _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ ● _ _ _ ○ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ ●
+        "#,
+        )
+        .unwrap();
+        let svg = board.to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(3, svg.matches(r#"<circle class="stone black""#).count());
+        assert_eq!(3, svg.matches(r#"<circle class="stone white""#).count());
+        assert_eq!(5, svg.matches(r#"<circle class="star""#).count());
+        assert_eq!(18, svg.matches("<line ").count());
+        // The black stone at (2, 2) is drawn 3 cells from the top left corner
+        assert!(svg.contains(r#"<circle class="stone black" cx="90" cy="90""#));
+    }
+
+    #[test]
+    fn empty_board() {
+        let svg = Board::<BoardSize5x5>::new().to_svg();
+        assert_eq!(0, svg.matches("<circle").count());
+        assert_eq!(10, svg.matches("<line ").count());
+    }
+}
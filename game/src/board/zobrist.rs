@@ -0,0 +1,15 @@
+use super::{Player, Pos, pos::BoardSize};
+use crate::utils::mix;
+
+/// Random-looking key for a stone of `player` at `pos`. The hash of a position is the XOR of
+/// the keys of all its stones, so it can be updated incrementally when stones are added or removed.
+///
+/// Instead of a table of random numbers, we derive the keys by hashing the position and color.
+/// That keeps them the same for every board size and doesn't need any storage.
+pub fn stone_key<BS: BoardSize>(pos: Pos<BS>, player: Player) -> u64 {
+    let color = match player {
+        Player::Black => 0,
+        Player::White => 1,
+    };
+    mix((2 * pos.index() as u64 + color).wrapping_add(0x9E37_79B9_7F4A_7C15))
+}
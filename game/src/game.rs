@@ -1,13 +1,82 @@
+use derive_more::{Display, Error};
+use derive_where::derive_where;
 use enum_map::{EnumMap, enum_map};
+use std::collections::HashMap;
 
 use crate::{
-    NumStones,
+    Komi, Move, NumStones, Outcome, OutcomeMargin,
     analysis::{Analysis, GroupInfo},
-    board::{Board, BoardSize, PlaceStoneError, Player, Pos},
+    board::{Board, BoardSize, GtpVertexError, PlaceStoneError, Player, Pos, stone_key},
     group_stones::GroupId,
+    life_and_death::GroupStatus,
+    scoring::{Score, ScoreEstimate, ScoringRules},
+    utils::SmallSet,
 };
 
-#[cfg_attr(test, derive(Debug, PartialEq))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+    /// Players take turns placing stones or passing
+    Playing,
+    /// Both players passed. Dead stones need to be agreed on before counting with [Game::finish_scoring].
+    Scoring,
+    /// The game was counted, one player resigned, or it was declared void because of a cycle
+    Finished { outcome: Outcome },
+}
+
+/// Why a move given with [Game::play_gtp] couldn't be played
+#[derive(Error, Display, Debug)]
+pub enum GtpPlayError {
+    #[display("Invalid vertex: {source}")]
+    InvalidVertex { source: GtpVertexError },
+
+    #[display("Illegal move: {source}")]
+    IllegalMove { source: PlaceStoneError },
+}
+
+/// Which moves are forbidden because they would repeat a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KoRule {
+    /// Any move is allowed, even immediately retaking a ko
+    None,
+    /// A single stone that just captured a single stone can't be recaptured on the very next move
+    #[default]
+    Simple,
+    /// No move may recreate a board position that already occurred earlier in the game
+    PositionalSuperko,
+}
+
+/// How a move would repeat an earlier position, see [Game::repetition_info]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepetitionKind {
+    /// Immediately retakes a ko, recreating the position from before the opponent took it
+    Ko,
+    /// Recreates the position from `moves_ago` moves ago, counting passes.
+    /// This is only forbidden under [KoRule::PositionalSuperko].
+    Superko { moves_ago: usize },
+}
+
+impl RepetitionKind {
+    /// How many moves ago, counting passes and the move itself, the position occurred
+    pub fn moves_ago(&self) -> usize {
+        match self {
+            RepetitionKind::Ko => 2,
+            RepetitionKind::Superko { moves_ago } => *moves_ago,
+        }
+    }
+}
+
+/// What happened in a single turn, so it can be replayed or undone
+#[derive_where(Debug, Clone, PartialEq, Eq)]
+struct HistoryEntry<BS: BoardSize> {
+    player: Player,
+    /// Where the stone was placed, or `None` for a pass
+    placed: Option<Pos<BS>>,
+    /// Opponent stones removed by this move
+    captured: Vec<Pos<BS>>,
+}
+
+#[derive_where(Clone)]
+#[cfg_attr(test, derive(Debug))]
 pub struct Game<BS: BoardSize>
 where
     [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
@@ -18,8 +87,49 @@ where
     current_player: Player,
     num_captured_by: EnumMap<Player, NumStones<BS>>,
     analysis: Analysis<BS>,
+    /// Position that can't be played on the next move because it would immediately retake a ko
+    ko: Option<Pos<BS>>,
+    num_consecutive_passes: usize,
+    ko_rule: KoRule,
+    /// Whether a repeating cycle of positions ends the game without result, see [Game::detect_cycle]
+    void_on_cycle: bool,
+    /// Hashes of all board positions so far, for [KoRule::PositionalSuperko], with the number of
+    /// moves after which each one occurred last
+    seen_positions: HashMap<u64, usize>,
+    /// Points White gets at the end of the game, used when counting the score
+    komi: Komi,
+    /// Set once the game was counted or a player resigned
+    outcome: Option<Outcome>,
+    history: Vec<HistoryEntry<BS>>,
+    /// [Board::zobrist_hash] of the current board, updated with each move
+    hash: u64,
 }
 
+/// Tests compare games reached by playing moves against games set up with [Game::from_board],
+/// so equality only looks at the current position and not at how we got there.
+#[cfg(test)]
+impl<BS: BoardSize> PartialEq for Game<BS>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+            && self.current_player == other.current_player
+            && self.num_captured_by == other.num_captured_by
+            && self.analysis == other.analysis
+            && self.ko == other.ko
+            && self.num_consecutive_passes == other.num_consecutive_passes
+            && self.ko_rule == other.ko_rule
+            && self.komi == other.komi
+            && self.outcome == other.outcome
+            && self.hash == other.hash
+    }
+}
+
+/// Most liberties a group without eye space can have for [Game::likely_status] to call it dead
+const LIKELY_DEAD_MAX_LIBERTIES: usize = 2;
+
 impl<BS: BoardSize> Game<BS>
 where
     [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
@@ -28,6 +138,7 @@ where
     pub fn new() -> Self {
         let board = Board::new();
         let analysis = Analysis::analyze(&board);
+        let hash = board.zobrist_hash();
         Self {
             board,
             current_player: Player::Black,
@@ -35,61 +146,538 @@ where
                 _ => NumStones::from_usize(0),
             },
             analysis,
+            ko: None,
+            num_consecutive_passes: 0,
+            ko_rule: KoRule::default(),
+            void_on_cycle: false,
+            seen_positions: HashMap::from([(hash, 0)]),
+            komi: Komi::DEFAULT,
+            outcome: None,
+            history: Vec::new(),
+            hash,
+        }
+    }
+
+    /// Starts from an empty board and plays `moves` in order, starting with Black.
+    pub fn from_moves(moves: &[Move]) -> Result<Self, PlaceStoneError> {
+        let mut game = Self::new();
+        for game_move in moves {
+            game.play_move(game_move)?;
         }
+        Ok(game)
     }
 
-    #[cfg(test)]
+    /// Starts a game from an arbitrary position, e.g. a handicap setup or a problem diagram.
     pub fn from_board(
         board: Board<BS>,
         current_player: Player,
         num_captured_by: EnumMap<Player, NumStones<BS>>,
     ) -> Self {
         let analysis = Analysis::analyze(&board);
+        let hash = board.zobrist_hash();
         Self {
             board,
             current_player,
             num_captured_by,
             analysis,
+            ko: None,
+            num_consecutive_passes: 0,
+            ko_rule: KoRule::default(),
+            void_on_cycle: false,
+            seen_positions: HashMap::from([(hash, 0)]),
+            komi: Komi::DEFAULT,
+            outcome: None,
+            history: Vec::new(),
+            hash,
         }
     }
 
+    pub fn ko_rule(&self) -> KoRule {
+        self.ko_rule
+    }
+
+    pub fn set_ko_rule(&mut self, ko_rule: KoRule) {
+        self.ko_rule = ko_rule;
+    }
+
+    pub fn void_on_cycle(&self) -> bool {
+        self.void_on_cycle
+    }
+
+    /// Some rule sets end a game without result ([Outcome::Void]) when the players can't break a
+    /// repetition like triple ko. If enabled, the game ends as soon as a move completes a cycle found
+    /// by [Self::detect_cycle]. Off by default.
+    pub fn set_void_on_cycle(&mut self, void_on_cycle: bool) {
+        self.void_on_cycle = void_on_cycle;
+    }
+
+    pub fn komi(&self) -> Komi {
+        self.komi
+    }
+
+    pub fn set_komi(&mut self, komi: Komi) {
+        self.komi = komi;
+    }
+
     pub fn current_player(&self) -> Player {
         self.current_player
     }
 
+    /// Makes it `player`'s turn without playing a move, e.g. after setting up a problem or to see how
+    /// the opponent would answer. This is meant for setup and analysis; in normal play, turns only
+    /// change by placing a stone or passing. The ko restriction is lifted if the player changes,
+    /// since a ko only forbids the immediate recapture by the opponent.
+    pub fn set_to_move(&mut self, player: Player) {
+        if player != self.current_player {
+            self.current_player = player;
+            self.ko = None;
+        }
+    }
+
     pub fn board(&self) -> &Board<BS> {
         &self.board
     }
 
+    /// Checks whether the current player could place a stone at `pos`, without placing it.
+    pub fn is_legal(&self, pos: Pos<BS>) -> Result<(), PlaceStoneError> {
+        if self.is_game_over() {
+            return Err(PlaceStoneError::GameOver);
+        }
+        if self.board.is_occupied(pos) {
+            return Err(PlaceStoneError::CellOccupied);
+        }
+        if self.ko_rule != KoRule::None && self.ko == Some(pos) {
+            return Err(PlaceStoneError::Ko);
+        }
+        if self._is_suicide(pos) {
+            return Err(PlaceStoneError::Suicide);
+        }
+        if self.ko_rule == KoRule::PositionalSuperko
+            && self.seen_positions.contains_key(&self._hash_after(pos))
+        {
+            return Err(PlaceStoneError::Superko);
+        }
+        Ok(())
+    }
+
+    /// Whether the current player placing a stone at `pos` would recreate an earlier position, and
+    /// how far back. This doesn't depend on the [KoRule], so it can explain why a move is rejected.
+    /// `None` if the position is new, or if `pos` is occupied or suicide.
+    pub fn repetition_info(&self, pos: Pos<BS>) -> Option<RepetitionKind> {
+        if self.board.is_occupied(pos) || self._is_suicide(pos) {
+            return None;
+        }
+        if self.ko == Some(pos) {
+            return Some(RepetitionKind::Ko);
+        }
+        let seen_after = self.seen_positions.get(&self._hash_after(pos))?;
+        Some(RepetitionKind::Superko {
+            moves_ago: self.history.len() + 1 - seen_after,
+        })
+    }
+
+    /// [Board::zobrist_hash] of the board after the current player places a stone at `pos`
+    /// and captures what it takes. `pos` must be empty and not suicide.
+    fn _hash_after(&self, pos: Pos<BS>) -> u64 {
+        let player = self.current_player;
+        let opponent = player.other_player();
+        let captured_groups: SmallSet<[GroupId<BS>; 4]> = pos
+            .neighbors()
+            .map(|neighbor| self.analysis.group_at(neighbor))
+            .filter(|group| {
+                matches!(
+                    self.analysis.group_info(*group),
+                    GroupInfo::PlayerGroup { owner, liberties }
+                        if *owner == opponent && *liberties == NumStones::ONE
+                )
+            })
+            .collect();
+        let mut hash = self.hash ^ stone_key(pos, player);
+        for stone in Pos::all_positions() {
+            if captured_groups.contains(&self.analysis.group_at(stone)) {
+                hash ^= stone_key(stone, opponent);
+            }
+        }
+        hash
+    }
+
+    /// All positions the current player could place a stone at.
+    pub fn legal_moves(&self) -> impl Iterator<Item = Pos<BS>> + '_ {
+        Pos::all_positions().filter(|pos| self.is_legal(*pos).is_ok())
+    }
+
+    /// Whether the current player has no legal move left, e.g. because every empty point would be
+    /// suicide, so the only thing they can do is pass. Also true once the game is over.
+    pub fn must_pass(&self) -> bool {
+        self.legal_moves().next().is_none()
+    }
+
+    /// Legal moves next to a stone, orthogonally or diagonally, to limit the moves a bot needs to consider.
+    /// On an empty board, these are the star points, or all legal moves on boards too small to have any.
+    pub fn frontier_moves(&self) -> Vec<Pos<BS>> {
+        if self.board.occupied().next().is_none() {
+            return self
+                .legal_moves()
+                .filter(|pos| BS::star_points().is_empty() || pos.is_star_point())
+                .collect();
+        }
+        self.legal_moves()
+            .filter(|pos| {
+                let (x, y) = (pos.x(), pos.y());
+                let size = <BS as BoardSize>::SIZE;
+                (y.saturating_sub(1)..=(y + 1).min(size - 1)).any(|y| {
+                    (x.saturating_sub(1)..=(x + 1).min(size - 1))
+                        .any(|x| self.board.is_occupied(Pos::from_xy(x, y)))
+                })
+            })
+            .collect()
+    }
+
+    /// Whether placing a stone for `player` at `pos` would fill one of their own eyes,
+    /// which is almost never a good move.
+    pub fn is_eye_fill(&self, pos: Pos<BS>, player: Player) -> bool {
+        self.board.is_eye(pos, player)
+    }
+
+    fn _is_suicide(&self, pos: Pos<BS>) -> bool {
+        let player = self.current_player;
+        let neighbors = [pos.up(), pos.left(), pos.right(), pos.down()];
+        !neighbors.into_iter().flatten().any(|neighbor| {
+            match self.analysis.group_info(self.analysis.group_at(neighbor)) {
+                // An empty neighbor is a liberty for the new stone
+                GroupInfo::EmptyStonesGroup => true,
+                GroupInfo::PlayerGroup { owner, liberties } => {
+                    if *owner == player {
+                        // We connect to this group. `pos` is one of its liberties, if it has any other one, we keep it.
+                        *liberties > NumStones::ONE
+                    } else {
+                        // `pos` is the last liberty of this opponent group, so we capture it and get a liberty
+                        *liberties == NumStones::ONE
+                    }
+                }
+            }
+        })
+    }
+
     pub fn place_stone(&mut self, pos: Pos<BS>) -> Result<(), PlaceStoneError> {
-        self.board.set_if_empty(pos, self.current_player)?;
+        if let Err(error) = self.is_legal(pos) {
+            log::debug!("Rejected {} stone at {pos}: {error}", self.current_player);
+            return Err(error);
+        }
+        let connects_to_own_stone = [pos.up(), pos.left(), pos.right(), pos.down()]
+            .into_iter()
+            .flatten()
+            .any(|neighbor| self.board[neighbor] == Some(self.current_player));
+        self.board.set(pos, Some(self.current_player));
         self._update_analysis();
-        self._take_prisoners();
+        let captured = self._take_prisoners();
+        if !captured.is_empty() {
+            log::debug!(
+                "{} stone at {pos} captured {} stones",
+                self.current_player,
+                captured.len()
+            );
+        }
+        self.hash ^= stone_key(pos, self.current_player);
+        let opponent = self.current_player.other_player();
+        for captured_pos in &captured {
+            self.hash ^= stone_key(*captured_pos, opponent);
+        }
+        debug_assert_eq!(self.hash, self.board.zobrist_hash());
+        // The move is only added to the history below
+        self.seen_positions
+            .insert(self.hash, self.history.len() + 1);
+        self.ko = self._ko_after_move(pos, connects_to_own_stone, &captured);
+        self.num_consecutive_passes = 0;
+        self.history.push(HistoryEntry {
+            player: self.current_player,
+            placed: Some(pos),
+            captured,
+        });
         self.current_player = self.current_player.other_player();
+        if self.void_on_cycle && self.detect_cycle().is_some() {
+            self.outcome = Some(Outcome::Void);
+        }
+
+        Ok(())
+    }
 
+    /// If the current board already occurred more than two moves ago, the number of moves since
+    /// then, i.e. the length of the cycle. Two moves ago would be immediately retaking a ko, which
+    /// [KoRule::Simple] already prevents, but longer cycles like triple ko can repeat forever under
+    /// it. Passes count as moves. The most recent earlier occurrence is found by undoing the moves
+    /// in the history one by one.
+    pub fn detect_cycle(&self) -> Option<usize> {
+        let mut hash = self.hash;
+        for (index, entry) in self.history.iter().rev().enumerate() {
+            if let Some(placed) = entry.placed {
+                hash ^= stone_key(placed, entry.player);
+            }
+            for captured in &entry.captured {
+                hash ^= stone_key(*captured, entry.player.other_player());
+            }
+            let moves_ago = index + 1;
+            if moves_ago > 2 && hash == self.hash {
+                return Some(moves_ago);
+            }
+        }
+        None
+    }
+
+    /// Plays a move from a game record. Fails like [Self::place_stone], or with [PlaceStoneError::OutOfBounds]
+    /// if the coordinates don't fit on this board size.
+    pub fn play_move(&mut self, game_move: &Move) -> Result<(), PlaceStoneError> {
+        match game_move {
+            Move::Pass => self.pass_turn(),
+            Move::Place { x, y } => {
+                let (x, y) = (usize::from(*x), usize::from(*y));
+                // Moves may come from files with a different board size, don't panic on them
+                if x >= <BS as BoardSize>::SIZE || y >= <BS as BoardSize>::SIZE {
+                    return Err(PlaceStoneError::OutOfBounds { x, y });
+                }
+                self.place_stone(Pos::from_xy(x, y))?
+            }
+            Move::Resign => self.resign(),
+        }
         Ok(())
     }
 
+    /// A single stone that just captured a single stone and is left with only one liberty (the captured position)
+    /// could be immediately recaptured, which would repeat the position. That recapture is forbidden for one move.
+    fn _ko_after_move(
+        &self,
+        pos: Pos<BS>,
+        connects_to_own_stone: bool,
+        captured: &[Pos<BS>],
+    ) -> Option<Pos<BS>> {
+        let [captured_pos] = captured else {
+            return None;
+        };
+        if connects_to_own_stone {
+            return None;
+        }
+        match self.analysis.group_info(self.analysis.group_at(pos)) {
+            GroupInfo::PlayerGroup { liberties, .. } if *liberties == NumStones::ONE => {
+                Some(*captured_pos)
+            }
+            _ => None,
+        }
+    }
+
     fn _update_analysis(&mut self) {
         // TODO Instead of re-calculating the union find every turn, it's probably cheaper to keep it and update it when stones are placed. Also, is then maybe a flood fill actually faster than a union find since we don't have to update the whole board when a stone is placed?
-        self.analysis = Analysis::analyze(&self.board);
+        self.analysis.reanalyze(&self.board);
     }
 
+    /// Plays a move given like in the GTP `play` command: a vertex like `"D4"` (see
+    /// [Pos::from_gtp_vertex]) or `"pass"`, for `color`. Like GTP allows, `color` doesn't need to be
+    /// the player whose turn it is, see [Self::set_to_move]. If the move fails, the game is unchanged.
+    pub fn play_gtp(&mut self, color: Player, vertex: &str) -> Result<(), GtpPlayError> {
+        let pos = if vertex.eq_ignore_ascii_case("pass") {
+            None
+        } else {
+            Some(
+                Pos::from_gtp_vertex(vertex)
+                    .map_err(|source| GtpPlayError::InvalidVertex { source })?,
+            )
+        };
+        if self.is_game_over() {
+            return Err(GtpPlayError::IllegalMove {
+                source: PlaceStoneError::GameOver,
+            });
+        }
+        let (current_player, ko) = (self.current_player, self.ko);
+        self.set_to_move(color);
+        match pos {
+            Some(pos) => self.place_stone(pos).map_err(|source| {
+                self.current_player = current_player;
+                self.ko = ko;
+                GtpPlayError::IllegalMove { source }
+            }),
+            None => {
+                self.pass_turn();
+                Ok(())
+            }
+        }
+    }
+
+    /// Does nothing once the game is over
     pub fn pass_turn(&mut self) {
+        if self.is_game_over() {
+            return;
+        }
+        self.history.push(HistoryEntry {
+            player: self.current_player,
+            placed: None,
+            captured: Vec::new(),
+        });
         self.current_player = self.current_player.other_player();
-        // No need to take prisoners or update the board since no stone was placed
+        self.ko = None;
+        self.num_consecutive_passes += 1;
+        // No need to take prisoners or update the board since no stone was placed, but the position
+        // occurred again
+        self.seen_positions.insert(self.hash, self.history.len());
+    }
+
+    /// The current player gives up, ending the game. Does nothing once the game is over.
+    pub fn resign(&mut self) {
+        if self.is_game_over() {
+            return;
+        }
+        self.outcome = Some(Outcome::WithWinner {
+            winner: self.current_player.other_player(),
+            margin: OutcomeMargin::ByResign,
+        });
+    }
+
+    /// The player who resigned, if the game ended by resignation
+    pub fn resigned(&self) -> Option<Player> {
+        match self.outcome {
+            Some(Outcome::WithWinner {
+                winner,
+                margin: OutcomeMargin::ByResign,
+            }) => Some(winner.other_player()),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        if let Some(outcome) = self.outcome {
+            GamePhase::Finished { outcome }
+        } else if self.num_consecutive_passes >= 2 {
+            GamePhase::Scoring
+        } else {
+            GamePhase::Playing
+        }
+    }
+
+    /// The game ends when both players pass in a row or one of them resigns
+    pub fn is_game_over(&self) -> bool {
+        self.phase() != GamePhase::Playing
+    }
+
+    /// Counts the game after both players passed, with `dead_stones` removed from the board and
+    /// [Self::komi] added for White, and finishes it. Returns `None` if the game isn't in the
+    /// [GamePhase::Scoring] phase.
+    pub fn finish_scoring(
+        &mut self,
+        dead_stones: &[Pos<BS>],
+        rules: ScoringRules,
+    ) -> Option<Outcome> {
+        if self.phase() != GamePhase::Scoring {
+            return None;
+        }
+        let outcome = self.score(dead_stones).outcome(rules, self.komi);
+        self.outcome = Some(outcome);
+        Some(outcome)
+    }
+
+    /// All stones connected to the stone at `pos`, or an empty list if `pos` is empty.
+    pub fn group_stones_at(&self, pos: Pos<BS>) -> Vec<Pos<BS>> {
+        if !self.board.is_occupied(pos) {
+            return Vec::new();
+        }
+        let group = self.analysis.group_at(pos);
+        Pos::all_positions()
+            .filter(|pos| self.analysis.group_at(*pos) == group)
+            .collect()
     }
 
-    fn _take_prisoners(&mut self) {
+    /// A quick guess at the status of each group of stones, e.g. to color groups in a UI or to
+    /// suggest dead stones for scoring. This is only a heuristic and doesn't read anything out:
+    /// groups with two eyes (see [Analysis::count_eyes]) are alive, groups with at most two
+    /// liberties and no empty region bordered only by their owner are dead, and everything else
+    /// is unsettled. Use [Analysis::status_of_group] for an actual search.
+    pub fn likely_status(&self) -> HashMap<GroupId<BS>, GroupStatus> {
+        self.analysis
+            .groups()
+            .filter_map(|(group, info)| {
+                let GroupInfo::PlayerGroup { owner, liberties } = info else {
+                    return None;
+                };
+                let status = if self.analysis.count_eyes(group) >= 2 {
+                    GroupStatus::Alive
+                } else if liberties.into_usize() <= LIKELY_DEAD_MAX_LIBERTIES
+                    && !self._has_eye_space(group, *owner)
+                {
+                    GroupStatus::Dead
+                } else {
+                    GroupStatus::Unsettled
+                };
+                Some((group, status))
+            })
+            .collect()
+    }
+
+    /// Whether `group` touches an empty region that only borders `owner`'s stones
+    fn _has_eye_space(&self, group: GroupId<BS>, owner: Player) -> bool {
+        self.analysis.adjacent_groups(group).iter().any(|region| {
+            matches!(
+                self.analysis.group_info(*region),
+                GroupInfo::EmptyStonesGroup
+            ) && self.analysis.adjacent_groups(*region).iter().all(|group| {
+                matches!(
+                    self.analysis.group_info(*group),
+                    GroupInfo::PlayerGroup { owner: o, .. } if *o == owner
+                )
+            })
+        })
+    }
+
+    /// Score of the current position, after removing `dead_stones` from the board.
+    pub fn score(&self, dead_stones: &[Pos<BS>]) -> Score<BS> {
+        Score::count(
+            &self.board,
+            dead_stones,
+            self.num_captured_by,
+            self.current_player,
+        )
+    }
+
+    /// Estimate of the current position while the game is still going on, see [ScoreEstimate]
+    pub fn estimate_score(&self) -> ScoreEstimate<BS> {
+        ScoreEstimate::new(self.score(&[]), self.komi)
+    }
+
+    /// Score of the current board in the format of the GTP `final_score` command: "B+12.5", "W+3",
+    /// or "0" for a draw. Counted by area with `komi`, without removing any dead stones.
+    pub fn gtp_final_score_string(&self, komi: Komi) -> String {
+        match self.score(&[]).outcome(ScoringRules::Area, komi) {
+            Outcome::WithWinner {
+                winner,
+                margin: OutcomeMargin::ByPoints { points_times_two },
+            } => {
+                let winner = match winner {
+                    Player::Black => "B",
+                    Player::White => "W",
+                };
+                format!("{winner}+{}", points_times_two as f32 / 2.0)
+            }
+            Outcome::Draw => "0".to_string(),
+            outcome => unreachable!("Counting is won by points or a draw, but was {outcome:?}"),
+        }
+    }
+
+    /// Returns the positions of the opponent stones that were captured
+    ///
+    /// Opponent groups without liberties are captured first, which may give the placed stone's group its
+    /// liberties back. This is the order every common rule set uses, so it isn't configurable. Only afterwards
+    /// are our own groups without liberties removed, which [Self::is_legal] currently never allows since it
+    /// rejects suicide.
+    fn _take_prisoners(&mut self) -> Vec<Pos<BS>> {
         // First capture all opponent groups without liberties
-        self._player_takes_prisoners(self.current_player);
+        let mut captured = self._player_takes_prisoners(self.current_player);
+        // Groups are captured in the order of their group ids. Sort so that the result doesn't depend on the numbering.
+        captured.sort();
 
         // Then take our own stones as prisoners
         let opponent = self.current_player.other_player();
         self._player_takes_prisoners(opponent);
+
+        captured
     }
 
-    fn _player_takes_prisoners(&mut self, player: Player) {
+    fn _player_takes_prisoners(&mut self, player: Player) -> Vec<Pos<BS>> {
         let opponent = player.other_player();
         let mut groups_to_capture = Vec::new();
         for (group, group_info) in self.analysis.groups() {
@@ -100,16 +688,23 @@ where
                 }
             }
         }
+        let mut captured = Vec::new();
         for group in groups_to_capture {
-            let num_captured = self._capture_group(group);
+            let num_captured = self._capture_group(group, &mut captured);
             self.num_captured_by[player] += num_captured;
         }
+        captured
     }
 
-    fn _capture_group(&mut self, group_to_capture: GroupId<BS>) -> NumStones<BS> {
+    fn _capture_group(
+        &mut self,
+        group_to_capture: GroupId<BS>,
+        captured: &mut Vec<Pos<BS>>,
+    ) -> NumStones<BS> {
         let mut num_captured = NumStones::ZERO;
         self.analysis.capture_group(group_to_capture, |pos| {
             self.board.set(pos, None);
+            captured.push(pos);
             num_captured += NumStones::ONE;
         });
 
@@ -121,11 +716,147 @@ where
     pub fn num_captured_by(&self, player: Player) -> NumStones<BS> {
         self.num_captured_by[player]
     }
+
+    /// Prisoners captured by each player so far, formatted as "Black: 3 | White: 1" so frontends
+    /// display them the same way
+    pub fn captures_summary(&self) -> String {
+        format!(
+            "{}: {} | {}: {}",
+            Player::Black,
+            self.num_captured_by[Player::Black].into_usize(),
+            Player::White,
+            self.num_captured_by[Player::White].into_usize(),
+        )
+    }
+
+    /// Zobrist hash of the current board, see [Board::zobrist_hash]
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Number of stones placed and turns passed so far. Setup stones of [Self::from_board] don't count.
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Where the last stone was placed, or `None` if the last move was a pass or no move was played yet
+    pub fn last_move(&self) -> Option<Pos<BS>> {
+        self.history.last().and_then(|entry| entry.placed)
+    }
+
+    /// Opponent stones captured by the last move, in board order. Empty if it was a pass or didn't capture anything.
+    ///
+    /// This is reset with every stone placed and every pass, so it never reports captures of earlier moves.
+    /// A move rejected as illegal doesn't count as a move and keeps the previous value.
+    pub fn last_captures(&self) -> &[Pos<BS>] {
+        self.history
+            .last()
+            .map(|entry| entry.captured.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// A simple deterministic engine: capture as many stones as possible, otherwise put an opponent group
+    /// in atari, otherwise play the [Self::frontier_moves] move that leaves the new stone with the most
+    /// liberties. Never fills its own eye or plays into self-atari, except to take a ko the opponent
+    /// can't immediately take back. `None` means the engine passes.
+    pub fn heuristic_move(&self) -> Option<Pos<BS>> {
+        let player = self.current_player;
+        let liberties_after = |pos: Pos<BS>| {
+            self.analysis
+                .liberties_after_move(&self.board, pos, player)
+                .map_or(0, |liberties| liberties.into_usize())
+        };
+        let takes_ko = |pos: Pos<BS>, num_captured: usize| {
+            // A single stone that captured a single stone and is left with one liberty, see _ko_after_move
+            self.ko_rule != KoRule::None
+                && num_captured == 1
+                && !pos
+                    .neighbors()
+                    .any(|neighbor| self.board[neighbor] == Some(player))
+                && liberties_after(pos) == 1
+        };
+        // max_by_key picks the last of equal elements, reverse so ties go to the first one in board order
+        if let Some((pos, _)) = self
+            .capturing_moves(1)
+            .into_iter()
+            .filter(|(pos, num_captured)| {
+                liberties_after(*pos) >= 2 || takes_ko(*pos, *num_captured)
+            })
+            .rev()
+            .max_by_key(|(_, num_captured)| *num_captured)
+        {
+            return Some(pos);
+        }
+
+        let puts_in_atari = |pos: Pos<BS>| {
+            pos.neighbors().any(|neighbor| {
+                matches!(
+                    self.analysis.group_info(self.analysis.group_at(neighbor)),
+                    GroupInfo::PlayerGroup { owner, liberties }
+                        if *owner != player && liberties.into_usize() == 2
+                )
+            })
+        };
+        let candidates: Vec<Pos<BS>> = self
+            .frontier_moves()
+            .into_iter()
+            .filter(|pos| !self.is_eye_fill(*pos, player) && liberties_after(*pos) >= 2)
+            .collect();
+        if let Some(pos) = candidates.iter().find(|pos| puts_in_atari(**pos)) {
+            return Some(*pos);
+        }
+        candidates
+            .into_iter()
+            .rev()
+            .max_by_key(|pos| liberties_after(*pos))
+    }
+
+    /// Legal moves for the current player that would capture at least `min` (and at least one)
+    /// opponent stones, together with the number of stones they capture.
+    pub fn capturing_moves(&self, min: usize) -> Vec<(Pos<BS>, usize)> {
+        self._num_captured_per_move()
+            .filter(|(_, num_captured)| *num_captured > 0 && *num_captured >= min)
+            .collect()
+    }
+
+    /// Legal moves for the current player that capture exactly `n` opponent stones, e.g. to
+    /// generate "capture exactly three stones" problems. With `n == 0`, the moves that don't capture.
+    pub fn moves_capturing_exactly(&self, n: usize) -> Vec<Pos<BS>> {
+        self._num_captured_per_move()
+            .filter(|(_, num_captured)| *num_captured == n)
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    fn _num_captured_per_move(&self) -> impl Iterator<Item = (Pos<BS>, usize)> + '_ {
+        let player = self.current_player;
+        // Ko was already checked by legal_moves, so playing on a copy of the board is enough
+        self.legal_moves().map(move |pos| {
+            let mut board = self.board;
+            let captured = board
+                .place_and_capture(pos, player)
+                .expect("legal_moves only returns legal moves");
+            (pos, captured.len())
+        })
+    }
+
+    /// Value of each legal move for the current player, as `eval` rates the game after the move.
+    /// Meant for debugging engines, e.g. to display the values as a heatmap.
+    pub fn move_value_map(&self, eval: impl Fn(&Game<BS>) -> f32) -> Vec<(Pos<BS>, f32)> {
+        self.legal_moves()
+            .map(|pos| {
+                let mut game = self.clone();
+                game.place_stone(pos)
+                    .expect("legal_moves only returns legal moves");
+                (pos, eval(&game))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::board::{BoardSize5x5, BoardSize13x13};
+    use crate::board::{BoardSize5x5, BoardSize7x7, BoardSize9x9, BoardSize13x13};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -303,4 +1034,1285 @@ mod tests {
             game
         );
     }
+
+    #[test]
+    fn capture_opponent_before_capturing_own_group() {
+        // White's move at (2, 0) takes the last liberty of its own three stones and of the two black
+        // stones at the same time. Capturing the black stones first keeps the white stones alive.
+        let mut game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ ● ○
+            ● ○ ● ● ○
+            _ ● ○ ○ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+            Player::White,
+        );
+        game.place_stone(Pos::from_xy(2, 0)).unwrap();
+        assert_eq!(
+            &Board::from_str(
+                r#"
+                ● _ ● ● ○
+                ● _ ● ● ○
+                _ ● ○ ○ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#
+            )
+            .unwrap(),
+            game.board()
+        );
+        assert_eq!(
+            NumStones::from_usize(2),
+            game.num_captured_by(Player::White)
+        );
+        assert_eq!(NumStones::ZERO, game.num_captured_by(Player::Black));
+    }
+
+    fn game_from_str<BS: BoardSize>(board: &str, current_player: Player) -> Game<BS>
+    where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+        [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+    {
+        Game::from_board(
+            Board::from_str(board).unwrap(),
+            current_player,
+            enum_map! {
+                _ => NumStones::ZERO,
+            },
+        )
+    }
+
+    #[test]
+    fn captures_summary() {
+        assert_eq!(
+            "Black: 0 | White: 0",
+            Game::<BoardSize5x5>::new().captures_summary()
+        );
+        let game = Game::<BoardSize5x5>::from_board(
+            Board::new(),
+            Player::Black,
+            enum_map! {
+                Player::Black => NumStones::from_usize(3),
+                Player::White => NumStones::from_usize(12),
+            },
+        );
+        assert_eq!("Black: 3 | White: 12", game.captures_summary());
+    }
+
+    #[test]
+    fn must_pass_when_only_suicide_points_remain() {
+        let board = Board::from_str(
+            r#"
+            _ ○ ○ ○ ○
+            ○ ○ ○ ○ ○
+            ○ ○ _ ○ ○
+            ○ ○ ○ ○ ○
+            ○ ○ ○ ○ _
+        "#,
+        )
+        .unwrap();
+        let no_captures = enum_map! { _ => NumStones::ZERO };
+        // Black still has three liberties, so every White stone would be captured right away
+        let game = Game::<BoardSize5x5>::from_board(board, Player::White, no_captures);
+        assert!(game.must_pass());
+        // Black may fill their own eyes
+        let game = Game::<BoardSize5x5>::from_board(board, Player::Black, no_captures);
+        assert!(!game.must_pass());
+        assert!(!Game::<BoardSize5x5>::new().must_pass());
+    }
+
+    mod detect_cycle {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        // Three kos at the top, middle and bottom. In each one, either the black stone at x=1
+        // or the white stone at x=2 can capture the other. White holds the first two, Black the last.
+        const TRIPLE_KO: &str = r#"
+            _ ● ○ _ _ _ _ _ _
+            ● _ ● ○ _ _ _ _ _
+            _ ● ○ _ _ _ _ _ _
+            _ ● ○ _ _ _ _ _ _
+            ● _ ● ○ _ _ _ _ _
+            _ ● ○ _ _ _ _ _ _
+            _ ● ○ _ _ _ _ _ _
+            ● ○ _ ○ _ _ _ _ _
+            _ ● ○ _ _ _ _ _ _
+        "#;
+
+        /// Each player takes the ko the opponent doesn't just forbid, until the start repeats
+        const CYCLE: [(usize, usize); 6] = [(1, 1), (2, 7), (1, 4), (2, 1), (1, 7), (2, 4)];
+
+        fn triple_ko() -> Game<BoardSize9x9> {
+            game_from_str(TRIPLE_KO, Player::Black)
+        }
+
+        #[test]
+        fn triple_ko_is_a_cycle() {
+            let mut game = triple_ko();
+            let start = *game.board();
+            for (x, y) in CYCLE {
+                assert_eq!(None, game.detect_cycle());
+                game.place_stone(Pos::from_xy(x, y)).unwrap();
+            }
+            assert_eq!(&start, game.board());
+            assert_eq!(Some(6), game.detect_cycle());
+            // Without void_on_cycle, the players can keep going
+            assert_eq!(GamePhase::Playing, game.phase());
+            game.place_stone(Pos::from_xy(1, 1)).unwrap();
+            assert_eq!(Some(6), game.detect_cycle());
+        }
+
+        #[test]
+        fn void_on_cycle() {
+            let mut game = triple_ko();
+            game.set_void_on_cycle(true);
+            for (x, y) in &CYCLE[..5] {
+                game.place_stone(Pos::from_xy(*x, *y)).unwrap();
+                assert_eq!(GamePhase::Playing, game.phase());
+            }
+            game.place_stone(Pos::from_xy(2, 4)).unwrap();
+            assert_eq!(
+                GamePhase::Finished {
+                    outcome: Outcome::Void
+                },
+                game.phase()
+            );
+        }
+
+        #[test]
+        fn retaking_a_ko_is_not_a_cycle() {
+            let mut game = triple_ko();
+            game.set_ko_rule(KoRule::None);
+            game.place_stone(Pos::from_xy(1, 1)).unwrap();
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            assert_eq!(None, game.detect_cycle());
+        }
+    }
+
+    mod play_gtp {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn play_stone() {
+            let mut game = Game::<BoardSize9x9>::new();
+            game.play_gtp(Player::Black, "D4").unwrap();
+            assert_eq!(Some(Player::Black), game.board()[Pos::from_xy(3, 5)]);
+            assert_eq!(Player::White, game.current_player());
+        }
+
+        #[test]
+        fn pass() {
+            let mut game = Game::<BoardSize9x9>::new();
+            game.play_gtp(Player::Black, "pass").unwrap();
+            game.play_gtp(Player::White, "PASS").unwrap();
+            assert_eq!(GamePhase::Scoring, game.phase());
+            assert!(matches!(
+                game.play_gtp(Player::Black, "pass"),
+                Err(GtpPlayError::IllegalMove {
+                    source: PlaceStoneError::GameOver
+                })
+            ));
+        }
+
+        #[test]
+        fn color_out_of_turn() {
+            let mut game = Game::<BoardSize9x9>::new();
+            game.play_gtp(Player::White, "E5").unwrap();
+            assert_eq!(Some(Player::White), game.board()[Pos::from_xy(4, 4)]);
+            assert_eq!(Player::Black, game.current_player());
+        }
+
+        #[test]
+        fn invalid_vertex() {
+            let mut game = Game::<BoardSize9x9>::new();
+            for vertex in ["I3", "Z1", "A10", "resign"] {
+                assert!(matches!(
+                    game.play_gtp(Player::Black, vertex),
+                    Err(GtpPlayError::InvalidVertex { .. })
+                ));
+            }
+            assert_eq!(0, game.move_count());
+        }
+
+        #[test]
+        fn illegal_move_leaves_game_unchanged() {
+            let mut game = Game::<BoardSize9x9>::new();
+            game.play_gtp(Player::Black, "D4").unwrap();
+            assert!(matches!(
+                game.play_gtp(Player::Black, "D4"),
+                Err(GtpPlayError::IllegalMove {
+                    source: PlaceStoneError::CellOccupied
+                })
+            ));
+            assert_eq!(Player::White, game.current_player());
+            assert_eq!(1, game.move_count());
+        }
+    }
+
+    #[test]
+    fn move_count() {
+        let mut game = Game::<BoardSize9x9>::new();
+        assert_eq!(0, game.move_count());
+        game.place_stone(Pos::from_xy(2, 2)).unwrap();
+        game.pass_turn();
+        game.place_stone(Pos::from_xy(3, 3)).unwrap();
+        assert_eq!(3, game.move_count());
+        // Illegal moves and moves after the game ended don't count
+        assert!(game.place_stone(Pos::from_xy(3, 3)).is_err());
+        game.pass_turn();
+        game.pass_turn();
+        assert_eq!(5, game.move_count());
+        game.pass_turn();
+        assert_eq!(5, game.move_count());
+    }
+
+    #[test]
+    fn set_to_move() {
+        let mut game = Game::<BoardSize9x9>::new();
+        game.set_to_move(Player::White);
+        assert_eq!(Player::White, game.current_player());
+        game.place_stone(Pos::from_xy(4, 4)).unwrap();
+        assert_eq!(Some(Player::White), game.board()[Pos::from_xy(4, 4)]);
+        assert_eq!(Player::Black, game.current_player());
+
+        // Setting the player whose turn it already is changes nothing
+        game.set_to_move(Player::Black);
+        game.place_stone(Pos::from_xy(3, 3)).unwrap();
+        assert_eq!(Some(Player::Black), game.board()[Pos::from_xy(3, 3)]);
+    }
+
+    #[test]
+    fn play_move_outside_of_board() {
+        let mut game = Game::<BoardSize9x9>::new();
+        assert!(matches!(
+            game.play_move(&Move::Place { x: 9, y: 3 }),
+            Err(PlaceStoneError::OutOfBounds { x: 9, y: 3 })
+        ));
+        assert!(matches!(
+            game.play_move(&Move::Place { x: 2, y: 18 }),
+            Err(PlaceStoneError::OutOfBounds { x: 2, y: 18 })
+        ));
+        // The failed moves didn't change anything
+        assert_eq!(Player::Black, game.current_player());
+        game.play_move(&Move::Place { x: 8, y: 8 }).unwrap();
+    }
+
+    #[test]
+    fn resign_ends_game() {
+        let mut game = Game::<BoardSize5x5>::new();
+        game.place_stone(Pos::from_xy(2, 2)).unwrap();
+        assert!(!game.is_game_over());
+        game.resign();
+        assert!(game.is_game_over());
+        assert_eq!(Some(Player::White), game.resigned());
+    }
+
+    mod phase {
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn new_game_is_playing() {
+            assert_eq!(GamePhase::Playing, Game::<BoardSize5x5>::new().phase());
+        }
+
+        #[test]
+        fn single_pass_keeps_playing() {
+            let mut game = Game::<BoardSize5x5>::new();
+            game.pass_turn();
+            assert_eq!(GamePhase::Playing, game.phase());
+            game.place_stone(Pos::from_xy(2, 2)).unwrap();
+            game.pass_turn();
+            assert_eq!(GamePhase::Playing, game.phase());
+        }
+
+        #[test]
+        fn two_passes_start_scoring() {
+            let mut game = Game::<BoardSize5x5>::new();
+            game.place_stone(Pos::from_xy(2, 2)).unwrap();
+            game.pass_turn();
+            game.pass_turn();
+            assert_eq!(GamePhase::Scoring, game.phase());
+            assert!(matches!(
+                game.place_stone(Pos::from_xy(0, 0)),
+                Err(PlaceStoneError::GameOver)
+            ));
+            assert_eq!(None, game.board()[Pos::from_xy(0, 0)]);
+        }
+
+        #[test]
+        fn scoring_finishes_game() {
+            let mut game = Game::<BoardSize5x5>::new();
+            game.place_stone(Pos::from_xy(2, 2)).unwrap();
+            game.pass_turn();
+            game.pass_turn();
+            // Black has the whole board: 25 points against 7.5 komi
+            let expected = Outcome::WithWinner {
+                winner: Player::Black,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 35,
+                },
+            };
+            assert_eq!(Some(expected), game.finish_scoring(&[], ScoringRules::Area));
+            assert_eq!(GamePhase::Finished { outcome: expected }, game.phase());
+            assert_eq!(None, game.finish_scoring(&[], ScoringRules::Area));
+        }
+
+        #[test]
+        fn komi_is_used_for_scoring() {
+            let mut game = Game::<BoardSize5x5>::new();
+            assert_eq!(Komi::DEFAULT, game.komi());
+            game.set_komi(Komi::new(30.5).unwrap());
+            assert_eq!(Komi::new(30.5).unwrap(), game.komi());
+
+            game.place_stone(Pos::from_xy(2, 2)).unwrap();
+            game.pass_turn();
+            game.pass_turn();
+            // Black's 25 points aren't enough against 30.5 komi
+            assert_eq!(
+                Some(Outcome::WithWinner {
+                    winner: Player::White,
+                    margin: OutcomeMargin::ByPoints {
+                        points_times_two: 11,
+                    },
+                }),
+                game.finish_scoring(&[], ScoringRules::Area)
+            );
+        }
+
+        #[test]
+        fn cannot_finish_scoring_while_playing() {
+            let mut game = Game::<BoardSize5x5>::new();
+            assert_eq!(None, game.finish_scoring(&[], ScoringRules::Area));
+            assert_eq!(GamePhase::Playing, game.phase());
+        }
+
+        #[test]
+        fn resigning_finishes_game() {
+            let mut game = Game::<BoardSize5x5>::new();
+            game.resign();
+            assert_eq!(
+                GamePhase::Finished {
+                    outcome: Outcome::WithWinner {
+                        winner: Player::White,
+                        margin: OutcomeMargin::ByResign,
+                    }
+                },
+                game.phase()
+            );
+            assert!(matches!(
+                game.place_stone(Pos::from_xy(0, 0)),
+                Err(PlaceStoneError::GameOver)
+            ));
+            // Passing or resigning again doesn't change anything
+            game.pass_turn();
+            game.resign();
+            assert_eq!(Some(Player::Black), game.resigned());
+            assert_eq!(Player::Black, game.current_player());
+        }
+    }
+
+    #[test]
+    fn estimate_score_mid_game() {
+        let game = game_from_str::<BoardSize5x5>(
+            r#"
+            _ ○ ● _ _
+            _ ○ ● _ _
+            ○ ○ ● ● ●
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+            Player::Black,
+        );
+        assert!(!game.is_game_over());
+        let estimate = game.estimate_score();
+        assert_eq!(NumStones::from_usize(2), estimate.territory(Player::Black));
+        assert_eq!(NumStones::from_usize(4), estimate.territory(Player::White));
+        // The open bottom rows touch both colors and stay neutral
+        assert_eq!(NumStones::from_usize(6), estimate.points(Player::Black));
+        assert_eq!(NumStones::from_usize(9), estimate.points(Player::White));
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 21,
+                },
+            },
+            estimate.outcome()
+        );
+    }
+
+    mod likely_status {
+        use crate::board::BoardSize9x9;
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn alive_dead_and_unsettled_groups() {
+            let game = game_from_str::<BoardSize9x9>(
+                r#"
+                _ ● _ ● ○ _ _ _ _
+                ● ● ● ● ○ _ _ _ _
+                ○ ○ ○ ○ ○ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ ● ● ● _ _
+                _ _ _ _ ● ○ ● _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            let status = game.likely_status();
+            let status_at = |x, y| status[&game.analysis.group_at(Pos::from_xy(x, y))];
+
+            assert_eq!(4, status.len());
+            // White's corner group has two eyes
+            assert_eq!(GroupStatus::Alive, status_at(0, 1));
+            // The black stone is in atari without any eye space
+            assert_eq!(GroupStatus::Dead, status_at(5, 5));
+            // Both outside groups have plenty of liberties, but no eyes
+            assert_eq!(GroupStatus::Unsettled, status_at(0, 2));
+            assert_eq!(GroupStatus::Unsettled, status_at(4, 4));
+        }
+
+        #[test]
+        fn empty_board() {
+            assert!(Game::<BoardSize9x9>::new().likely_status().is_empty());
+        }
+    }
+
+    mod gtp_final_score_string {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        // Black has 7 stones and 3 points of territory, White 8 stones and 6 points
+        const FINISHED_BOARD: &str = r#"
+            _ ○ ● _ _
+            ○ ○ ● _ ●
+            _ ○ ● ● _
+            ○ ○ _ ● ●
+            _ ○ ● _ _
+        "#;
+
+        fn score_string(komi: f32) -> String {
+            game_from_str::<BoardSize5x5>(FINISHED_BOARD, Player::Black)
+                .gtp_final_score_string(Komi::new(komi).unwrap())
+        }
+
+        #[test]
+        fn white_wins() {
+            assert_eq!("W+4", score_string(0.0));
+            assert_eq!("W+11.5", score_string(7.5));
+        }
+
+        #[test]
+        fn black_wins() {
+            assert_eq!("B+2.5", score_string(-6.5));
+        }
+
+        #[test]
+        fn jigo() {
+            assert_eq!("0", score_string(-4.0));
+        }
+
+        #[test]
+        fn empty_board() {
+            assert_eq!(
+                "W+7.5",
+                Game::<BoardSize5x5>::new().gtp_final_score_string(Komi::DEFAULT)
+            );
+        }
+    }
+
+    mod frontier_moves {
+        use crate::board::{BoardSize5x5, BoardSize9x9};
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn sparse_position() {
+            let game = game_from_str::<BoardSize9x9>(
+                r#"
+                _ _ _ _ _ _ _ _ _
+                _ ○ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ _
+                _ _ _ _ _ _ _ _ ●
+            "#,
+                Player::Black,
+            );
+            let expected: Vec<Pos<BoardSize9x9>> = [
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (7, 7),
+                (8, 7),
+                (7, 8),
+            ]
+            .into_iter()
+            .map(|(x, y)| Pos::from_xy(x, y))
+            .collect();
+            assert_eq!(expected, game.frontier_moves());
+        }
+
+        #[test]
+        fn illegal_moves_are_excluded() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ● _ _ _
+                ● _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            let frontier = game.frontier_moves();
+            assert!(!frontier.contains(&Pos::from_xy(0, 0)));
+            assert!(frontier.contains(&Pos::from_xy(1, 1)));
+            assert!(frontier.contains(&Pos::from_xy(2, 1)));
+            assert!(!frontier.contains(&Pos::from_xy(3, 0)));
+        }
+
+        #[test]
+        fn empty_board_uses_star_points() {
+            let expected: Vec<Pos<BoardSize9x9>> = BoardSize9x9::star_points()
+                .iter()
+                .map(|(x, y)| Pos::from_xy(*x, *y))
+                .collect();
+            assert_eq!(expected, Game::<BoardSize9x9>::new().frontier_moves());
+        }
+
+        #[test]
+        fn empty_board_without_star_points() {
+            assert_eq!(25, Game::<BoardSize5x5>::new().frontier_moves().len());
+        }
+    }
+
+    mod heuristic_move {
+        use crate::board::{BoardSize5x5, BoardSize9x9};
+        use pretty_assertions::assert_eq;
+
+        use super::*;
+
+        #[test]
+        fn prefers_the_biggest_capture() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                ● ○ _ ○ ●
+                _ _ _ ○ ●
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            // (0, 1) would capture one stone, (4, 2) captures two
+            assert_eq!(Some(Pos::from_xy(4, 2)), game.heuristic_move());
+        }
+
+        #[test]
+        fn takes_ko() {
+            let mut game = game_from_str::<BoardSize5x5>(
+                r#"
+                ● _ ● _ _
+                ○ ● _ _ _
+                ○ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            // (1, 0) captures the corner stone and is left in atari, but the ko rule keeps White
+            // from taking it right back
+            assert_eq!(vec![(Pos::from_xy(1, 0), 1)], game.capturing_moves(1));
+            assert_eq!(Some(Pos::from_xy(1, 0)), game.heuristic_move());
+
+            // Without the ko rule, White could retake immediately
+            game.set_ko_rule(KoRule::None);
+            assert_ne!(Some(Pos::from_xy(1, 0)), game.heuristic_move());
+        }
+
+        #[test]
+        fn doesnt_capture_into_snapback() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                ● _ ○ ● _
+                ○ ● ● _ _
+                ○ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            // (1, 0) captures the corner stone, but White answers at (0, 0) and captures both
+            // black stones on the top edge
+            assert_eq!(vec![(Pos::from_xy(1, 0), 1)], game.capturing_moves(1));
+            assert_ne!(Some(Pos::from_xy(1, 0)), game.heuristic_move());
+        }
+
+        #[test]
+        fn puts_group_in_atari() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ _ _ _ _
+                _ _ ○ _ _
+                _ ○ ● _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            assert_eq!(Some(Pos::from_xy(3, 2)), game.heuristic_move());
+        }
+
+        #[test]
+        fn empty_board_plays_star_point() {
+            let pos = Game::<BoardSize9x9>::new().heuristic_move().unwrap();
+            assert!(pos.is_star_point());
+        }
+
+        #[test]
+        fn passes_without_good_moves() {
+            // Black's only empty points are its own eyes
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ○ ● _ ●
+                ○ ○ ● ● ●
+                ○ ○ ● _ ●
+                _ ○ ● ● ●
+                ○ ○ ● _ ●
+            "#,
+                Player::Black,
+            );
+            assert_eq!(None, game.heuristic_move());
+
+            let mut game = Game::<BoardSize5x5>::new();
+            game.resign();
+            assert_eq!(None, game.heuristic_move());
+        }
+    }
+
+    #[test]
+    fn last_move() {
+        let mut game = Game::<BoardSize5x5>::new();
+        assert_eq!(None, game.last_move());
+        game.place_stone(Pos::from_xy(1, 2)).unwrap();
+        assert_eq!(Some(Pos::from_xy(1, 2)), game.last_move());
+        game.place_stone(Pos::from_xy(3, 3)).unwrap();
+        assert_eq!(Some(Pos::from_xy(3, 3)), game.last_move());
+        game.pass_turn();
+        assert_eq!(None, game.last_move());
+    }
+
+    #[test]
+    fn capturing_two_groups_at_once() {
+        let white = [(0, 0), (1, 0), (3, 0), (4, 0), (4, 1)];
+        let black = [(0, 1), (1, 1), (2, 1), (3, 1), (4, 2)];
+        // Play the same shape in all four rotations, so the two groups get numbered in different orders
+        for rotations in 0..4 {
+            let pos = |xy: (usize, usize)| {
+                let (x, y) = (0..rotations).fold(xy, |(x, y), _| (4 - y, x));
+                Pos::<BoardSize5x5>::from_xy(x, y)
+            };
+            let mut board = Board::new();
+            board.set_many(white.map(|xy| (pos(xy), Some(Player::White))));
+            board.set_many(black.map(|xy| (pos(xy), Some(Player::Black))));
+            let mut game =
+                Game::from_board(board, Player::Black, enum_map! { _ => NumStones::ZERO });
+            game.place_stone(pos((2, 0))).unwrap();
+
+            assert_eq!(
+                NumStones::from_usize(5),
+                game.num_captured_by(Player::Black)
+            );
+            let mut expected_board = board;
+            expected_board.set_many(white.map(|xy| (pos(xy), None)));
+            expected_board.set(pos((2, 0)), Some(Player::Black));
+            assert_eq!(&expected_board, game.board());
+            let mut expected_captures = white.map(pos);
+            expected_captures.sort();
+            assert_eq!(&expected_captures, game.last_captures());
+        }
+    }
+
+    #[test]
+    fn last_captures() {
+        let mut game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ _ _
+            ● _ _ _ _
+            ○ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+            Player::Black,
+        );
+        assert_eq!(&[] as &[Pos<BoardSize5x5>], game.last_captures());
+        game.place_stone(Pos::from_xy(1, 1)).unwrap();
+        assert_eq!(
+            &[Pos::from_xy(0, 0), Pos::from_xy(0, 1)],
+            game.last_captures()
+        );
+        game.place_stone(Pos::from_xy(4, 4)).unwrap();
+        assert_eq!(&[] as &[Pos<BoardSize5x5>], game.last_captures());
+    }
+
+    #[test]
+    fn last_captures_are_reset_by_each_move() {
+        let mut game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+            Player::Black,
+        );
+        game.place_stone(Pos::from_xy(0, 1)).unwrap();
+        assert_eq!(&[Pos::from_xy(0, 0)], game.last_captures());
+
+        // Rejected moves don't change anything
+        assert!(game.place_stone(Pos::from_xy(0, 1)).is_err());
+        assert_eq!(&[Pos::from_xy(0, 0)], game.last_captures());
+
+        game.pass_turn();
+        assert_eq!(&[] as &[Pos<BoardSize5x5>], game.last_captures());
+    }
+
+    #[test]
+    fn zobrist_hash_is_maintained_across_captures() {
+        let mut game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ _ _
+            ● _ _ _ _
+            ○ _ _ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+            Player::Black,
+        );
+        let hash_before = game.zobrist_hash();
+        game.place_stone(Pos::from_xy(1, 1)).unwrap();
+        assert_eq!(2, game.last_captures().len());
+        assert_ne!(hash_before, game.zobrist_hash());
+        assert_eq!(game.board().zobrist_hash(), game.zobrist_hash());
+        game.place_stone(Pos::from_xy(0, 0)).unwrap();
+        assert_eq!(game.board().zobrist_hash(), game.zobrist_hash());
+    }
+
+    #[test]
+    fn from_moves_with_capture() {
+        let game = Game::<BoardSize5x5>::from_moves(&[
+            Move::Place { x: 1, y: 0 },
+            Move::Place { x: 0, y: 0 },
+            Move::Place { x: 0, y: 1 },
+            Move::Pass,
+            Move::Place { x: 2, y: 2 },
+        ])
+        .unwrap();
+        let expected_board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ○ _ _ _
+            ○ _ _ _ _
+            _ _ ○ _ _
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+        )
+        .unwrap();
+        assert_eq!(&expected_board, game.board());
+        assert_eq!(NumStones::ONE, game.num_captured_by(Player::Black));
+        assert_eq!(Player::White, game.current_player());
+    }
+
+    #[test]
+    fn from_moves_with_illegal_move() {
+        assert!(matches!(
+            Game::<BoardSize5x5>::from_moves(&[
+                Move::Place { x: 1, y: 0 },
+                Move::Place { x: 1, y: 0 },
+            ]),
+            Err(PlaceStoneError::CellOccupied)
+        ));
+    }
+
+    #[test]
+    fn capturing_moves() {
+        let game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ ○ _
+            _ _ ○ ● ○
+            _ _ ○ ● ○
+            _ _ _ _ _
+            _ _ ○ ● ○
+            "#,
+            Player::Black,
+        );
+        // (0, 1) captures the corner stone, (3, 3) captures both groups on the right at once
+        assert_eq!(
+            vec![(Pos::from_xy(0, 1), 1), (Pos::from_xy(3, 3), 3)],
+            game.capturing_moves(1)
+        );
+        assert_eq!(vec![(Pos::from_xy(3, 3), 3)], game.capturing_moves(2));
+        assert_eq!(
+            Vec::<(Pos<BoardSize5x5>, usize)>::new(),
+            game.capturing_moves(4)
+        );
+    }
+
+    #[test]
+    fn moves_capturing_exactly() {
+        let game = game_from_str::<BoardSize7x7>(
+            r#"
+            ● ○ ○ ● ● ○ _
+            _ _ _ ○ _ _ _
+            _ _ _ _ _ _ _
+            _ _ _ _ _ _ _
+            ● ○ _ _ _ _ _
+            ● ○ _ _ _ _ _
+            ● ○ _ _ _ _ _
+            "#,
+            Player::Black,
+        );
+        assert_eq!(vec![Pos::from_xy(0, 1)], game.moves_capturing_exactly(1));
+        assert_eq!(vec![Pos::from_xy(4, 1)], game.moves_capturing_exactly(2));
+        assert_eq!(vec![Pos::from_xy(0, 3)], game.moves_capturing_exactly(3));
+        assert_eq!(
+            Vec::<Pos<BoardSize7x7>>::new(),
+            game.moves_capturing_exactly(4)
+        );
+        assert_eq!(
+            game.legal_moves().count() - 3,
+            game.moves_capturing_exactly(0).len()
+        );
+    }
+
+    #[test]
+    fn move_value_map() {
+        let game = game_from_str::<BoardSize5x5>(
+            r#"
+            ● ○ _ ○ _
+            _ _ ○ ● ○
+            _ _ ○ ● ○
+            _ _ _ _ _
+            _ _ ○ ● ○
+            "#,
+            Player::Black,
+        );
+        let values =
+            game.move_value_map(|game| game.num_captured_by(Player::Black).into_usize() as f32);
+        assert_eq!(game.legal_moves().count(), values.len());
+        assert_eq!(
+            vec![(Pos::from_xy(0, 1), 1.0), (Pos::from_xy(3, 3), 3.0)],
+            values
+                .into_iter()
+                .filter(|(_, value)| *value != 0.0)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    mod logging {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use std::cell::RefCell;
+
+        thread_local! {
+            static RECORDS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        /// Records the messages logged on the current thread, so tests running in parallel
+        /// don't see each other's messages
+        struct TestLogger;
+
+        impl log::Log for TestLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                RECORDS.with_borrow_mut(|records| records.push(record.args().to_string()));
+            }
+
+            fn flush(&self) {}
+        }
+
+        fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+            static LOGGER: TestLogger = TestLogger;
+            // Fails if another test already installed it, which is fine
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(log::LevelFilter::Trace);
+            RECORDS.with_borrow_mut(Vec::clear);
+            f();
+            RECORDS.take()
+        }
+
+        #[test]
+        fn rejected_suicide_is_logged() {
+            let mut game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ● _ _ _
+                ● _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                "#,
+                Player::Black,
+            );
+            let logs = capture_logs(|| {
+                assert!(matches!(
+                    game.place_stone(Pos::from_xy(0, 0)),
+                    Err(PlaceStoneError::Suicide)
+                ));
+            });
+            assert_eq!(
+                vec![format!(
+                    "Rejected Black stone at {}: {}",
+                    Pos::<BoardSize5x5>::from_xy(0, 0),
+                    PlaceStoneError::Suicide
+                )],
+                logs
+            );
+        }
+
+        #[test]
+        fn capture_is_logged() {
+            let mut game = game_from_str::<BoardSize5x5>(
+                r#"
+                ● ○ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                "#,
+                Player::Black,
+            );
+            let logs = capture_logs(|| game.place_stone(Pos::from_xy(0, 1)).unwrap());
+            assert!(
+                logs.iter().any(|log| log.ends_with("captured 1 stones")),
+                "{logs:?}"
+            );
+        }
+    }
+
+    mod is_legal {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn empty_cell_is_legal() {
+            let game = Game::<BoardSize13x13>::new();
+            assert!(game.is_legal(Pos::from_xy(3, 3)).is_ok());
+        }
+
+        #[test]
+        fn occupied_cell_is_illegal() {
+            let mut game = Game::<BoardSize13x13>::new();
+            game.place_stone(Pos::from_xy(3, 3)).unwrap();
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(3, 3)),
+                Err(PlaceStoneError::CellOccupied)
+            ));
+        }
+
+        #[test]
+        fn suicide_is_illegal() {
+            let mut game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ○ _ _ _
+                ○ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::White,
+            );
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(0, 0)),
+                Err(PlaceStoneError::Suicide)
+            ));
+            assert!(matches!(
+                game.place_stone(Pos::from_xy(0, 0)),
+                Err(PlaceStoneError::Suicide)
+            ));
+            assert_eq!(None, game.board()[Pos::from_xy(0, 0)]);
+            assert_eq!(Player::White, game.current_player());
+        }
+
+        #[test]
+        fn connecting_to_group_with_remaining_liberties_is_not_suicide() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ● ○ _ _
+                ○ ● _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::White,
+            );
+            assert!(game.is_legal(Pos::from_xy(0, 0)).is_ok());
+        }
+
+        #[test]
+        fn filling_own_last_liberty_is_suicide() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ● ○ _ _
+                ● ● ○ _ _
+                ○ ○ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::White,
+            );
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(0, 0)),
+                Err(PlaceStoneError::Suicide)
+            ));
+        }
+
+        #[test]
+        fn capturing_is_not_suicide() {
+            let game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ○ ● _ _
+                ○ ● _ _ _
+                ● _ _ _ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::White,
+            );
+            assert!(game.is_legal(Pos::from_xy(0, 0)).is_ok());
+        }
+
+        const KO_BOARD: &str = r#"
+            _ ○ ● _ _
+            ○ ● _ ● _
+            _ ○ ● _ _
+            _ _ _ _ _
+            _ _ _ _ _
+        "#;
+
+        #[test]
+        fn immediately_retaking_ko_is_illegal() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            assert_eq!(None, game.board()[Pos::from_xy(1, 1)]);
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(1, 1)),
+                Err(PlaceStoneError::Ko)
+            ));
+            assert!(matches!(
+                game.place_stone(Pos::from_xy(1, 1)),
+                Err(PlaceStoneError::Ko)
+            ));
+            assert_eq!(Some(Player::Black), game.board()[Pos::from_xy(2, 1)]);
+        }
+
+        #[test]
+        fn retaking_ko_after_another_move_is_legal() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            game.place_stone(Pos::from_xy(4, 4)).unwrap();
+            game.place_stone(Pos::from_xy(4, 3)).unwrap();
+            assert!(game.is_legal(Pos::from_xy(1, 1)).is_ok());
+            game.place_stone(Pos::from_xy(1, 1)).unwrap();
+            assert_eq!(None, game.board()[Pos::from_xy(2, 1)]);
+        }
+
+        #[test]
+        fn pass_clears_ko() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            assert_eq!(Some(Pos::from_xy(1, 1)), game.ko);
+            game.pass_turn();
+            assert_eq!(None, game.ko);
+        }
+
+        #[test]
+        fn capturing_two_stones_is_not_ko() {
+            let mut game = game_from_str::<BoardSize5x5>(
+                r#"
+                _ ○ ● ● ○
+                ○ ● _ ● ○
+                _ ○ ● ○ _
+                _ _ _ _ _
+                _ _ _ _ _
+            "#,
+                Player::Black,
+            );
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            assert_eq!(
+                NumStones::from_usize(4),
+                game.num_captured_by(Player::Black)
+            );
+            assert_eq!(None, game.ko);
+        }
+
+        #[test]
+        fn immediately_retaking_ko_without_ko_rule_is_legal() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            game.set_ko_rule(KoRule::None);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            game.place_stone(Pos::from_xy(1, 1)).unwrap();
+            assert_eq!(None, game.board()[Pos::from_xy(2, 1)]);
+        }
+
+        #[test]
+        fn superko_forbids_immediately_retaking_ko() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            game.set_ko_rule(KoRule::PositionalSuperko);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(1, 1)),
+                Err(PlaceStoneError::Ko)
+            ));
+        }
+
+        /// Two kos: Black can take the one in the top left, White the one in the bottom right
+        const DOUBLE_KO_BOARD: &str = r#"
+            _ ○ ● _ _ _ _
+            ○ ● _ ● _ _ _
+            _ ○ ● _ _ _ _
+            _ _ _ _ _ _ _
+            _ _ _ _ ● ○ _
+            _ _ _ ● ○ _ ○
+            _ _ _ _ ● ○ _
+        "#;
+
+        /// Both players take a ko, Black passes so White can retake the first one, and then
+        /// Black retaking the second one would recreate the starting position
+        fn play_double_ko(ko_rule: KoRule) -> Game<BoardSize7x7> {
+            let mut game = game_from_str::<BoardSize7x7>(DOUBLE_KO_BOARD, Player::Black);
+            game.set_ko_rule(ko_rule);
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            game.place_stone(Pos::from_xy(5, 5)).unwrap();
+            game.pass_turn();
+            game.place_stone(Pos::from_xy(1, 1)).unwrap();
+            game
+        }
+
+        #[test]
+        fn simple_ko_allows_repeating_an_earlier_position() {
+            let mut game = play_double_ko(KoRule::Simple);
+            assert!(game.is_legal(Pos::from_xy(4, 5)).is_ok());
+            game.place_stone(Pos::from_xy(4, 5)).unwrap();
+            assert_eq!(&Board::from_str(DOUBLE_KO_BOARD).unwrap(), game.board());
+        }
+
+        #[test]
+        fn superko_forbids_repeating_an_earlier_position() {
+            let mut game = play_double_ko(KoRule::PositionalSuperko);
+            assert!(matches!(
+                game.is_legal(Pos::from_xy(4, 5)),
+                Err(PlaceStoneError::Superko)
+            ));
+            assert!(matches!(
+                game.place_stone(Pos::from_xy(4, 5)),
+                Err(PlaceStoneError::Superko)
+            ));
+            // Other moves are still fine
+            assert!(game.is_legal(Pos::from_xy(3, 3)).is_ok());
+        }
+
+        #[test]
+        fn repetition_info_for_ko() {
+            let mut game = game_from_str::<BoardSize5x5>(KO_BOARD, Player::Black);
+            assert_eq!(None, game.repetition_info(Pos::from_xy(2, 1)));
+            game.place_stone(Pos::from_xy(2, 1)).unwrap();
+            let repetition = game.repetition_info(Pos::from_xy(1, 1));
+            assert_eq!(Some(RepetitionKind::Ko), repetition);
+            assert_eq!(2, repetition.unwrap().moves_ago());
+            // The ko is reported even if it may be retaken
+            game.set_ko_rule(KoRule::None);
+            assert_eq!(
+                Some(RepetitionKind::Ko),
+                game.repetition_info(Pos::from_xy(1, 1))
+            );
+        }
+
+        #[test]
+        fn repetition_info_for_superko() {
+            let game = play_double_ko(KoRule::Simple);
+            // Retaking the second ko recreates the starting position from five moves ago
+            assert_eq!(
+                Some(RepetitionKind::Superko { moves_ago: 5 }),
+                game.repetition_info(Pos::from_xy(4, 5))
+            );
+            assert_eq!(None, game.repetition_info(Pos::from_xy(3, 3)));
+            // Occupied
+            assert_eq!(None, game.repetition_info(Pos::from_xy(1, 1)));
+        }
+
+        #[test]
+        fn repetition_info_counts_from_the_last_pass() {
+            let mut game = play_double_ko(KoRule::None);
+            // Black passed after White took the second ko, and that position comes back after
+            // Black retakes the second ko, White takes it again and Black takes the first one
+            game.place_stone(Pos::from_xy(4, 5)).unwrap();
+            game.place_stone(Pos::from_xy(5, 5)).unwrap();
+            assert_eq!(
+                Some(RepetitionKind::Superko { moves_ago: 4 }),
+                game.repetition_info(Pos::from_xy(2, 1))
+            );
+        }
+    }
+
+    #[test]
+    fn game_ends_after_two_consecutive_passes() {
+        let mut game = Game::<BoardSize5x5>::new();
+        assert!(!game.is_game_over());
+        game.pass_turn();
+        assert!(!game.is_game_over());
+        game.place_stone(Pos::from_xy(2, 2)).unwrap();
+        game.pass_turn();
+        assert!(!game.is_game_over());
+        game.pass_turn();
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn group_stones_at() {
+        let game = game_from_str::<BoardSize5x5>(
+            r#"
+            _ ○ ○ _ _
+            _ _ ○ _ ●
+            _ _ _ _ ●
+            _ _ _ _ _
+            _ _ _ _ _
+        "#,
+            Player::Black,
+        );
+        assert_eq!(
+            vec![Pos::from_xy(1, 0), Pos::from_xy(2, 0), Pos::from_xy(2, 1)],
+            game.group_stones_at(Pos::from_xy(2, 1))
+        );
+        assert_eq!(
+            vec![Pos::from_xy(4, 1), Pos::from_xy(4, 2)],
+            game.group_stones_at(Pos::from_xy(4, 1))
+        );
+        assert_eq!(
+            Vec::<Pos<BoardSize5x5>>::new(),
+            game.group_stones_at(Pos::from_xy(0, 0))
+        );
+    }
 }
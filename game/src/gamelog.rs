@@ -1,9 +1,495 @@
-use crate::{Board, BoardSize};
+use derive_more::{Display, Error};
+use enum_map::enum_map;
+use std::cell::RefCell;
 
+use crate::{
+    Board, BoardSize, Game, Komi, Move, NumStones, Outcome, OutcomeMargin, PlaceStoneError, Player,
+    Pos,
+};
+
+/// Why a move submitted with [GameLog::apply_remote_move] doesn't fit the log
+#[derive(Error, Display, Debug)]
+pub enum SyncError {
+    #[display("Expected move {expected} but got move {actual}")]
+    OutOfOrder { expected: usize, actual: usize },
+
+    #[display("Expected a move by {expected} but got one by {actual}")]
+    WrongPlayer { expected: Player, actual: Player },
+
+    #[display("Move is illegal in the current position: {source}")]
+    IllegalMove { source: PlaceStoneError },
+
+    #[display("Move {move_index} already in the log is illegal: {source}")]
+    IllegalEarlierMove {
+        move_index: usize,
+        source: PlaceStoneError,
+    },
+}
+
+/// The moves of a game, so it can be replayed and reviewed.
+///
+/// Positions are cached the first time they're needed, so stepping back and forth through a game
+/// replays each move only once. The cost is a [Board] per move in memory, which is well below
+/// 100KB even for a long 19x19 game. Not caching would save that memory, but every step back
+/// would have to replay the game from the start.
 pub struct GameLog<BS: BoardSize>
 where
     [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
 {
-    komi: f32,
+    komi: Komi,
     initial_board: Board<BS>,
+    /// Player of the first move, usually Black but e.g. White in handicap games
+    first_player: Player,
+    /// Moves in the order they were played, starting with [Self::first_player]
+    moves: Vec<Move>,
+    positions: RefCell<PositionCache<BS>>,
+}
+
+/// Positions for a prefix of the moves. Pushing moves keeps it valid, so it never needs to be reset.
+struct PositionCache<BS: BoardSize>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    /// Game after the last cached position, to continue replaying from there
+    game: Game<BS>,
+    /// `boards[n]` is the board before move `n`
+    boards: Vec<Board<BS>>,
+}
+
+impl<BS: BoardSize> GameLog<BS>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    pub fn new(komi: Komi, initial_board: Board<BS>) -> Self {
+        Self::with_first_player(komi, initial_board, Player::Black)
+    }
+
+    /// Like [Self::new], but `first_player` makes the first move, e.g. White after Black's
+    /// handicap stones were set up on `initial_board`
+    pub fn with_first_player(komi: Komi, initial_board: Board<BS>, first_player: Player) -> Self {
+        let mut game = Game::from_board(
+            initial_board,
+            first_player,
+            enum_map! { _ => NumStones::ZERO },
+        );
+        game.set_komi(komi);
+        Self {
+            komi,
+            initial_board,
+            first_player,
+            moves: Vec::new(),
+            positions: RefCell::new(PositionCache {
+                game,
+                boards: vec![initial_board],
+            }),
+        }
+    }
+
+    pub fn komi(&self) -> Komi {
+        self.komi
+    }
+
+    pub fn initial_board(&self) -> &Board<BS> {
+        &self.initial_board
+    }
+
+    pub fn first_player(&self) -> Player {
+        self.first_player
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn push(&mut self, game_move: Move) {
+        self.moves.push(game_move);
+    }
+
+    /// Appends a move submitted by `player`, e.g. by a remote client in correspondence play.
+    /// `expected_move_number` is the index in [Self::moves] the submitter expects the move to get,
+    /// so moves that were submitted based on an outdated log are rejected instead of applied out of order.
+    /// The move must also be by the player whose turn it is and legal in the current position,
+    /// and all moves already in the log must be legal.
+    pub fn apply_remote_move(
+        &mut self,
+        expected_move_number: usize,
+        player: Player,
+        game_move: Move,
+    ) -> Result<(), SyncError> {
+        let next_move_number = self.moves.len();
+        if expected_move_number != next_move_number {
+            return Err(SyncError::OutOfOrder {
+                expected: next_move_number,
+                actual: expected_move_number,
+            });
+        }
+        let to_move = self.player_of_move(next_move_number);
+        if player != to_move {
+            return Err(SyncError::WrongPlayer {
+                expected: to_move,
+                actual: player,
+            });
+        }
+        if let Err(source) = self._replay_until(next_move_number) {
+            // The cache stops before the first illegal move
+            let move_index = self.positions.borrow().boards.len() - 1;
+            return Err(SyncError::IllegalEarlierMove { move_index, source });
+        }
+        let mut positions = self.positions.borrow_mut();
+        check_legal(&positions.game, &game_move)
+            .map_err(|source| SyncError::IllegalMove { source })?;
+        positions
+            .game
+            .play_move(&game_move)
+            .expect("The move was checked to be legal");
+        let board = *positions.game.board();
+        positions.boards.push(board);
+        drop(positions);
+        self.push(game_move);
+        Ok(())
+    }
+
+    fn player_of_move(&self, move_index: usize) -> Player {
+        if move_index.is_multiple_of(2) {
+            self.first_player
+        } else {
+            self.first_player.other_player()
+        }
+    }
+
+    /// The board before move `move_index` was played, or after all moves if `move_index` is the
+    /// number of moves. Fails if one of the moves before it is illegal.
+    pub fn position_before(&self, move_index: usize) -> Result<Board<BS>, PlaceStoneError> {
+        assert!(move_index <= self.moves.len(), "Move index out of range");
+        self._replay_until(move_index)?;
+        Ok(self.positions.borrow().boards[move_index])
+    }
+
+    /// All positions from the end of the game back to the initial board, for stepping backward
+    /// through a game. Fails if one of the moves is illegal.
+    pub fn iter_positions_rev(
+        &self,
+    ) -> Result<impl Iterator<Item = Board<BS>> + use<BS>, PlaceStoneError> {
+        self._replay_until(self.moves.len())?;
+        Ok(self.positions.borrow().boards.clone().into_iter().rev())
+    }
+
+    /// The board before each move and after the last one, together with the number of moves played
+    /// to reach it. Moves are replayed lazily as the iterator advances and cached like for
+    /// [Self::position_before], so iterating again doesn't replay anything. Boards are returned by
+    /// value since the cache can grow while iterating. Ends after the position before the first
+    /// illegal move.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, Board<BS>)> + '_ {
+        (0..=self.moves.len())
+            .map_while(|move_index| Some((move_index, self.position_before(move_index).ok()?)))
+    }
+
+    fn _replay_until(&self, move_index: usize) -> Result<(), PlaceStoneError> {
+        let mut positions = self.positions.borrow_mut();
+        while positions.boards.len() <= move_index {
+            let game_move = self.moves[positions.boards.len() - 1];
+            positions.game.play_move(&game_move)?;
+            let board = *positions.game.board();
+            positions.boards.push(board);
+        }
+        Ok(())
+    }
+
+    /// Outcome as far as it follows from the moves alone. A resignation decides the game,
+    /// anything else is [Outcome::Unfinished] since counting needs to know the dead stones.
+    pub fn outcome(&self) -> Outcome {
+        match self.moves.iter().position(|m| *m == Move::Resign) {
+            Some(index) => {
+                let resigning_player = self.player_of_move(index);
+                Outcome::WithWinner {
+                    winner: resigning_player.other_player(),
+                    margin: OutcomeMargin::ByResign,
+                }
+            }
+            None => Outcome::Unfinished,
+        }
+    }
+}
+
+/// Whether [Game::play_move] would succeed, without playing the move
+fn check_legal<BS: BoardSize>(game: &Game<BS>, game_move: &Move) -> Result<(), PlaceStoneError>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    match game_move {
+        // Passing and resigning never fail, they do nothing once the game is over
+        Move::Pass | Move::Resign => Ok(()),
+        Move::Place { x, y } => {
+            let (x, y) = (usize::from(*x), usize::from(*y));
+            if x >= <BS as BoardSize>::SIZE || y >= <BS as BoardSize>::SIZE {
+                return Err(PlaceStoneError::OutOfBounds { x, y });
+            }
+            game.is_legal(Pos::from_xy(x, y))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardSize9x9;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn log_with_moves(moves: &[Move]) -> GameLog<BoardSize9x9> {
+        let mut log = GameLog::new(Komi::DEFAULT, Board::new());
+        for game_move in moves {
+            log.push(*game_move);
+        }
+        log
+    }
+
+    #[test]
+    fn step_forward_and_back() {
+        let moves = [
+            Move::Place { x: 1, y: 0 },
+            Move::Place { x: 0, y: 0 },
+            Move::Place { x: 0, y: 1 },
+            Move::Pass,
+            Move::Place { x: 4, y: 4 },
+        ];
+        let log = log_with_moves(&moves);
+
+        let mut game = Game::<BoardSize9x9>::new();
+        let mut expected = vec![*game.board()];
+        for game_move in &moves {
+            game.play_move(game_move).unwrap();
+            expected.push(*game.board());
+        }
+        // The white stone in the corner is captured
+        assert_eq!(None, expected[3][Pos::from_xy(0, 0)]);
+
+        // Jump to the end first, then step back and forth
+        assert_eq!(expected[5], log.position_before(5).unwrap());
+        for move_index in (0..=5).rev() {
+            assert_eq!(
+                expected[move_index],
+                log.position_before(move_index).unwrap()
+            );
+        }
+        for (move_index, expected_board) in expected.iter().enumerate() {
+            assert_eq!(*expected_board, log.position_before(move_index).unwrap());
+        }
+
+        let backward: Vec<_> = log.iter_positions_rev().unwrap().collect();
+        assert_eq!(expected.into_iter().rev().collect::<Vec<_>>(), backward);
+    }
+
+    #[test]
+    fn positions_match_fresh_replay() {
+        let moves = [
+            Move::Place { x: 1, y: 0 },
+            Move::Place { x: 0, y: 0 },
+            Move::Place { x: 0, y: 1 },
+            Move::Pass,
+            Move::Place { x: 4, y: 4 },
+        ];
+        let log = log_with_moves(&moves);
+        let positions: Vec<_> = log.positions().collect();
+        assert_eq!(moves.len() + 1, positions.len());
+        for (move_index, board) in positions {
+            let fresh = Game::<BoardSize9x9>::from_moves(&moves[..move_index]).unwrap();
+            assert_eq!(fresh.board(), &board, "after {move_index} moves");
+        }
+        // All positions are cached now, so iterating again doesn't replay anything
+        assert_eq!(moves.len() + 1, log.positions.borrow().boards.len());
+        assert_eq!(moves.len() + 1, log.positions().count());
+    }
+
+    #[test]
+    fn positions_end_before_illegal_move() {
+        let log = log_with_moves(&[
+            Move::Place { x: 2, y: 2 },
+            Move::Place { x: 3, y: 3 },
+            Move::Place { x: 2, y: 2 },
+        ]);
+        let move_indices: Vec<usize> = log.positions().map(|(index, _)| index).collect();
+        assert_eq!(vec![0, 1, 2], move_indices);
+    }
+
+    #[test]
+    fn positions_after_push() {
+        let mut log = log_with_moves(&[Move::Place { x: 2, y: 2 }]);
+        assert_eq!(
+            Some(Player::Black),
+            log.position_before(1).unwrap()[Pos::from_xy(2, 2)]
+        );
+        log.push(Move::Place { x: 3, y: 3 });
+        let board = log.position_before(2).unwrap();
+        assert_eq!(Some(Player::Black), board[Pos::from_xy(2, 2)]);
+        assert_eq!(Some(Player::White), board[Pos::from_xy(3, 3)]);
+    }
+
+    #[test]
+    fn positions_after_illegal_move() {
+        let log = log_with_moves(&[Move::Place { x: 2, y: 2 }, Move::Place { x: 2, y: 2 }]);
+        assert!(log.position_before(1).is_ok());
+        assert!(matches!(
+            log.position_before(2),
+            Err(PlaceStoneError::CellOccupied)
+        ));
+        assert!(log.iter_positions_rev().is_err());
+    }
+
+    #[test]
+    fn remote_moves_in_order() {
+        let mut log = log_with_moves(&[]);
+        log.apply_remote_move(0, Player::Black, Move::Place { x: 2, y: 2 })
+            .unwrap();
+        log.apply_remote_move(1, Player::White, Move::Pass).unwrap();
+        log.apply_remote_move(2, Player::Black, Move::Place { x: 3, y: 3 })
+            .unwrap();
+        assert_eq!(
+            &[
+                Move::Place { x: 2, y: 2 },
+                Move::Pass,
+                Move::Place { x: 3, y: 3 }
+            ],
+            log.moves()
+        );
+        assert_eq!(
+            Some(Player::Black),
+            log.position_before(3).unwrap()[Pos::from_xy(3, 3)]
+        );
+    }
+
+    #[test]
+    fn remote_move_out_of_order() {
+        let mut log = log_with_moves(&[Move::Place { x: 2, y: 2 }]);
+        // Submitted before the client saw Black's first move
+        assert!(matches!(
+            log.apply_remote_move(0, Player::White, Move::Place { x: 3, y: 3 }),
+            Err(SyncError::OutOfOrder {
+                expected: 1,
+                actual: 0
+            })
+        ));
+        // Submitted too early
+        assert!(matches!(
+            log.apply_remote_move(2, Player::Black, Move::Place { x: 3, y: 3 }),
+            Err(SyncError::OutOfOrder {
+                expected: 1,
+                actual: 2
+            })
+        ));
+        assert_eq!(1, log.moves().len());
+    }
+
+    #[test]
+    fn remote_move_by_wrong_player() {
+        let mut log = log_with_moves(&[Move::Place { x: 2, y: 2 }]);
+        assert!(matches!(
+            log.apply_remote_move(1, Player::Black, Move::Place { x: 3, y: 3 }),
+            Err(SyncError::WrongPlayer {
+                expected: Player::White,
+                actual: Player::Black
+            })
+        ));
+        assert_eq!(1, log.moves().len());
+    }
+
+    #[test]
+    fn illegal_remote_move() {
+        let mut log = log_with_moves(&[Move::Place { x: 2, y: 2 }]);
+        assert!(matches!(
+            log.apply_remote_move(1, Player::White, Move::Place { x: 2, y: 2 }),
+            Err(SyncError::IllegalMove {
+                source: PlaceStoneError::CellOccupied
+            })
+        ));
+        assert_eq!(1, log.moves().len());
+    }
+
+    #[test]
+    fn remote_move_after_illegal_move_in_the_log() {
+        let mut log = log_with_moves(&[
+            Move::Place { x: 2, y: 2 },
+            Move::Place { x: 2, y: 2 },
+            Move::Place { x: 4, y: 4 },
+        ]);
+        assert!(matches!(
+            log.apply_remote_move(3, Player::White, Move::Place { x: 3, y: 3 }),
+            Err(SyncError::IllegalEarlierMove {
+                move_index: 1,
+                source: PlaceStoneError::CellOccupied
+            })
+        ));
+        assert_eq!(3, log.moves().len());
+    }
+
+    #[test]
+    fn remote_moves_with_white_first() {
+        let mut initial_board = Board::new();
+        initial_board.set(Pos::from_xy(2, 2), Some(Player::Black));
+        initial_board.set(Pos::from_xy(6, 6), Some(Player::Black));
+        let mut log =
+            GameLog::<BoardSize9x9>::with_first_player(Komi::DEFAULT, initial_board, Player::White);
+        assert!(matches!(
+            log.apply_remote_move(0, Player::Black, Move::Place { x: 3, y: 3 }),
+            Err(SyncError::WrongPlayer {
+                expected: Player::White,
+                actual: Player::Black
+            })
+        ));
+        log.apply_remote_move(0, Player::White, Move::Place { x: 3, y: 3 })
+            .unwrap();
+        log.apply_remote_move(1, Player::Black, Move::Place { x: 4, y: 4 })
+            .unwrap();
+        let board = log.position_before(2).unwrap();
+        assert_eq!(Some(Player::White), board[Pos::from_xy(3, 3)]);
+        assert_eq!(Some(Player::Black), board[Pos::from_xy(4, 4)]);
+
+        log.apply_remote_move(2, Player::White, Move::Resign)
+            .unwrap();
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::Black,
+                margin: OutcomeMargin::ByResign,
+            },
+            log.outcome()
+        );
+    }
+
+    #[test]
+    fn no_moves_is_unfinished() {
+        assert_eq!(Outcome::Unfinished, log_with_moves(&[]).outcome());
+    }
+
+    #[test]
+    fn white_resigns() {
+        let log = log_with_moves(&[
+            Move::Place { x: 2, y: 2 },
+            Move::Place { x: 6, y: 6 },
+            Move::Pass,
+            Move::Resign,
+        ]);
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::Black,
+                margin: OutcomeMargin::ByResign,
+            },
+            log.outcome()
+        );
+    }
+
+    #[test]
+    fn black_resigns() {
+        let log = log_with_moves(&[Move::Place { x: 2, y: 2 }, Move::Pass, Move::Resign]);
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByResign,
+            },
+            log.outcome()
+        );
+    }
 }
@@ -4,6 +4,7 @@ use super::{grouped_stones::GroupedStones, union_find::UnionFindAlgorithm};
 
 /// Assigns each stone on the board a number, so that connected stones have the same number.
 /// Groups are consecutive numbers starting from 0, where 0 is the first group found.
+/// Connected empty cells form groups as well, and [GroupedStones::owner] tells them apart from stones.
 pub fn group_connected_stones<BS: BoardSize>(board: &Board<BS>) -> GroupedStones<BS>
 where
     [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
@@ -59,7 +60,7 @@ where
         result.add_to_group(current_pos, group_for_current);
     }
 
-    result.finalize()
+    result.finalize(board)
 }
 
 #[cfg(test)]
@@ -163,31 +164,42 @@ mod tests {
         }
     }
 
-    fn parse_groups_from_string<BS: BoardSize>(input: &str) -> Result<GroupedStones<BS>, String>
+    fn parse_groups_from_string<BS: BoardSize>(
+        board: &Board<BS>,
+        input: &str,
+    ) -> Result<GroupedStones<BS>, String>
     where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
         [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
     {
         let mut seen_groups = HashSet::new();
         let mut parser = testutils::NumbersParser::new(input);
         let mut groups = [GroupId::ZERO; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
         let mut num_groups = GroupId::ZERO;
+        let mut owners = Vec::new();
         for i in 0..(<BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE) {
             let group =
                 GroupId::from_usize(usize::try_from(parser.next_number().unwrap()).unwrap());
             groups[i] = group;
             if seen_groups.insert(group) {
+                assert_eq!(num_groups, group, "Groups must be numbered in order");
+                owners.push(board[Pos::from_index(i)]);
                 num_groups.increment();
             }
         }
         assert!(parser.next_number().is_none());
-        Ok(GroupedStones::new(groups, num_groups))
+        Ok(GroupedStones::new(groups, num_groups, owners))
     }
 
-    fn assert_groups_eq<BS: BoardSize>(grouped: &GroupedStones<BS>, expected_groups_str: &str)
-    where
+    fn assert_groups_eq<BS: BoardSize>(
+        board: &Board<BS>,
+        grouped: &GroupedStones<BS>,
+        expected_groups_str: &str,
+    ) where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
         [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
     {
-        let expected_groups = parse_groups_from_string::<BS>(expected_groups_str)
+        let expected_groups = parse_groups_from_string::<BS>(board, expected_groups_str)
             .expect("Failed to parse expected groups from string");
         assert_eq!(
             expected_groups, *grouped,
@@ -211,6 +223,7 @@ mod tests {
         let grouped = group_connected_stones(&board);
 
         assert_groups_eq(
+            &board,
             &grouped,
             r#"
             0 1 2 3 3
@@ -245,6 +258,7 @@ mod tests {
         let grouped = group_connected_stones(&board);
 
         assert_groups_eq(
+            &board,
             &grouped,
             r#"
             0 1 1 1 1 1 0
@@ -25,7 +25,61 @@ impl<BS: BoardSize> GroupId<BS> {
         self.index.into_usize()
     }
 
+    /// Group ids are also used to count groups, and a board can't have more groups than cells.
+    /// Going beyond that means the grouping has a bug, so it's caught in debug builds.
     pub fn increment(&mut self) {
+        debug_assert!(
+            self.into_usize() < <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE,
+            "More groups than cells on the board"
+        );
         self.index += NumStones::<BS>::ONE;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Board, Player, Pos,
+        board::{BoardSize5x5, BoardSize19x19},
+        group_stones::group_connected_stones,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn checkerboard<BS: BoardSize>() -> Board<BS>
+    where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    {
+        let mut board = Board::new();
+        for pos in Pos::all_positions() {
+            let player = if (pos.x() + pos.y()) % 2 == 0 {
+                Player::Black
+            } else {
+                Player::White
+            };
+            board.set(pos, Some(player));
+        }
+        board
+    }
+
+    #[test]
+    fn checkerboard_has_one_group_per_cell() {
+        assert_eq!(
+            GroupId::from_usize(25),
+            group_connected_stones(&checkerboard::<BoardSize5x5>()).num_groups()
+        );
+        assert_eq!(
+            GroupId::from_usize(361),
+            group_connected_stones(&checkerboard::<BoardSize19x19>()).num_groups()
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "More groups than cells on the board")]
+    fn increment_beyond_number_of_cells_panics() {
+        let mut group = GroupId::<BoardSize5x5>::from_usize(25);
+        group.increment();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{BoardSize, board::Pos};
+use crate::{BoardSize, Player, board::Pos};
 use derive_where::derive_where;
 
 use super::group_id::GroupId;
@@ -11,6 +11,10 @@ where
     pos_to_group: [GroupId<BS>; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
 
     num_groups: GroupId<BS>,
+
+    /// The color of the stones in each group, or `None` for a region of empty cells.
+    /// Stones and empty cells are never in the same group.
+    owners: Vec<Option<Player>>,
 }
 
 impl<BS: BoardSize> GroupedStones<BS>
@@ -20,10 +24,13 @@ where
     pub fn new(
         pos_to_group: [GroupId<BS>; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
         num_groups: GroupId<BS>,
+        owners: Vec<Option<Player>>,
     ) -> Self {
+        debug_assert_eq!(num_groups.into_usize(), owners.len());
         Self {
             pos_to_group,
             num_groups,
+            owners,
         }
     }
 
@@ -31,11 +38,21 @@ where
         self.pos_to_group[pos.index()]
     }
 
+    pub fn owner(&self, group: GroupId<BS>) -> Option<Player> {
+        self.owners[group.into_usize()]
+    }
+
     pub fn num_groups(&self) -> GroupId<BS> {
         self.num_groups
     }
 
-    pub fn into(self) -> [GroupId<BS>; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE] {
+    /// Hands over the mapping from positions to groups, e.g. to [crate::Analysis], which keeps its own
+    /// info about each group. This moves the array out instead of cloning it, and is inlined so the
+    /// move into its new owner can be elided.
+    #[inline]
+    pub fn into_pos_to_group(
+        self,
+    ) -> [GroupId<BS>; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE] {
         self.pos_to_group
     }
 }
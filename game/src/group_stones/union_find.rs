@@ -1,4 +1,7 @@
-use crate::board::{BoardSize, Pos};
+use crate::{
+    Board,
+    board::{BoardSize, Pos},
+};
 
 use super::{GroupId, GroupedStones};
 
@@ -9,6 +12,11 @@ where
     // Nodes pointing to themselves are roots and representatives of their group.
     // Invariant A: forall i: groups[i].index() <= i (i.e. each node points to a parent that is either further up, or if in the same row then to the left, or itself)
     groups: [Pos<BS>; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
+
+    // Which nodes were already written to. The initial state of `groups` is meaningless, so reading a node
+    // that wasn't written yet is a bug. Only tracked in debug builds to keep release builds fast.
+    #[cfg(debug_assertions)]
+    written: [bool; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
 }
 
 impl<BS: BoardSize> UnionFindAlgorithm<BS>
@@ -20,18 +28,33 @@ where
             // Initial state is all nodes belong to the same group.
             // Doesn't matter though because we never read any of those before writing to it.
             groups: [Pos::from_xy(0, 0); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
+            #[cfg(debug_assertions)]
+            written: [false; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE],
         }
     }
 
+    fn parent(&self, pos: Pos<BS>) -> Pos<BS> {
+        #[cfg(debug_assertions)]
+        assert!(
+            self.written[pos.index()],
+            "Read union-find node {pos} before it was written"
+        );
+        self.groups[pos.index()]
+    }
+
     pub fn add_to_group(&mut self, pos: Pos<BS>, group_root: Pos<BS>) {
         assert!(group_root <= pos, "Invariant A violated");
         self.groups[pos.index()] = group_root;
+        #[cfg(debug_assertions)]
+        {
+            self.written[pos.index()] = true;
+        }
     }
 
     pub fn find_group_root(&mut self, current_pos: Pos<BS>) -> Pos<BS> {
         let mut current_pos = current_pos;
-        let mut parent_pos = self.groups[current_pos.index()];
-        let mut grandparent_pos = self.groups[parent_pos.index()];
+        let mut parent_pos = self.parent(current_pos);
+        let mut grandparent_pos = self.parent(parent_pos);
         while parent_pos != current_pos {
             // current_pos is not the root yet
 
@@ -41,7 +64,7 @@ where
             // And move one closer to the root
             current_pos = parent_pos;
             parent_pos = grandparent_pos;
-            grandparent_pos = self.groups[parent_pos.index()];
+            grandparent_pos = self.parent(parent_pos);
         }
         current_pos
     }
@@ -59,23 +82,62 @@ where
         }
     }
 
-    pub fn finalize(&mut self) -> GroupedStones<BS> {
+    /// `board` is the board the groups were built from, used to record the owner of each group.
+    pub fn finalize(&mut self, board: &Board<BS>) -> GroupedStones<BS>
+    where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    {
         let mut groups = [GroupId::ZERO; <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE];
         // Because of invariant A, we know we'll always see the group root before seeing any other members of the group.
         // This means to get consecutive group numbers, we can just assign each root a new number, and for non-roots
         // we know we've already assigned a number to the root and can look it up.
         let mut current_group_number = GroupId::ZERO;
+        let mut owners = Vec::new();
         for current_pos in Pos::all_positions() {
             let root_of_current_group = self.find_group_root(current_pos);
             if root_of_current_group == current_pos {
                 // This is a root, assign it a new group number
                 groups[current_pos.index()] = current_group_number;
+                owners.push(board[current_pos]);
                 current_group_number.increment();
             } else {
                 // Not a root, find its group number from the root
                 groups[current_pos.index()] = groups[root_of_current_group.index()];
             }
         }
-        GroupedStones::new(groups, current_group_number)
+        GroupedStones::new(groups, current_group_number, owners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardSize5x5;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn find_root_of_written_nodes() {
+        let mut union_find = UnionFindAlgorithm::<BoardSize5x5>::new();
+        union_find.add_to_group(Pos::from_xy(0, 0), Pos::from_xy(0, 0));
+        union_find.add_to_group(Pos::from_xy(1, 0), Pos::from_xy(0, 0));
+        union_find.add_to_group(Pos::from_xy(2, 0), Pos::from_xy(2, 0));
+        assert_eq!(
+            Pos::from_xy(0, 0),
+            union_find.find_group_root(Pos::from_xy(1, 0))
+        );
+        assert_eq!(
+            Pos::from_xy(2, 0),
+            union_find.find_group_root(Pos::from_xy(2, 0))
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Read union-find node 1/0 before it was written")]
+    fn reading_unwritten_node_panics() {
+        let mut union_find = UnionFindAlgorithm::<BoardSize5x5>::new();
+        union_find.add_to_group(Pos::from_xy(0, 0), Pos::from_xy(0, 0));
+        union_find.find_group_root(Pos::from_xy(1, 0));
     }
 }
@@ -0,0 +1,96 @@
+use derive_more::{Display, Error};
+
+#[derive(Error, Display, Debug, Clone, Copy, PartialEq)]
+pub enum KomiError {
+    #[display("Komi must be a multiple of 0.5 but was {komi}")]
+    NotAHalfInteger { komi: f32 },
+}
+
+/// Points White gets as compensation for Black moving first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Komi {
+    // Multiplied by two so we can represent half points without float drift
+    points_times_two: i32,
+}
+
+impl Komi {
+    /// 7.5 points, the komi used by most area scoring rule sets
+    pub const DEFAULT: Self = Self {
+        points_times_two: 15,
+    };
+
+    pub fn new(points: f32) -> Result<Self, KomiError> {
+        let times_two = points * 2.0;
+        if !times_two.is_finite() || (times_two - times_two.round()).abs() >= 0.0001 {
+            return Err(KomiError::NotAHalfInteger { komi: points });
+        }
+        Ok(Self {
+            points_times_two: times_two.round() as i32,
+        })
+    }
+
+    pub const fn from_points_times_two(points_times_two: i32) -> Self {
+        Self { points_times_two }
+    }
+
+    pub fn points_times_two(&self) -> i32 {
+        self.points_times_two
+    }
+
+    pub fn points(&self) -> f32 {
+        self.points_times_two as f32 / 2.0
+    }
+}
+
+impl Default for Komi {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl std::fmt::Display for Komi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.points())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_integer_komi() {
+        let komi = Komi::new(6.5).unwrap();
+        assert_eq!(13, komi.points_times_two());
+        assert_eq!(6.5, komi.points());
+    }
+
+    #[test]
+    fn integer_komi() {
+        let komi = Komi::new(6.0).unwrap();
+        assert_eq!(12, komi.points_times_two());
+        assert_eq!("6", komi.to_string());
+    }
+
+    #[test]
+    fn negative_komi() {
+        let komi = Komi::new(-0.5).unwrap();
+        assert_eq!(-1, komi.points_times_two());
+        assert_eq!("-0.5", komi.to_string());
+    }
+
+    #[test]
+    fn non_half_integer_komi_is_rejected() {
+        assert_eq!(
+            Err(KomiError::NotAHalfInteger { komi: 6.3 }),
+            Komi::new(6.3)
+        );
+        assert!(Komi::new(f32::NAN).is_err());
+        assert!(Komi::new(f32::INFINITY).is_err());
+    }
+
+    #[test]
+    fn default_komi() {
+        assert_eq!(Komi::new(7.5).unwrap(), Komi::default());
+    }
+}
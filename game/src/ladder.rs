@@ -0,0 +1,157 @@
+use crate::{Analysis, Board, BoardSize, GroupId, GroupInfo, NumStones, Pos};
+
+impl<BS: BoardSize> Analysis<BS>
+where
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    /// Whether `group`, which must be in atari, gets captured in a ladder (shicho) when its owner
+    /// keeps extending and the opponent keeps putting it back into atari.
+    ///
+    /// The group escapes if it gets three or more liberties or can capture one of the
+    /// surrounding stones. Ko is ignored. Returns false for groups that aren't in atari.
+    pub fn is_laddered(&self, group: GroupId<BS>) -> bool {
+        let GroupInfo::PlayerGroup { liberties, .. } = self.group_info(group) else {
+            return false;
+        };
+        if liberties.into_usize() != 1 {
+            return false;
+        }
+        let Some(prey_pos) = Pos::all_positions().find(|pos| self.group_at(*pos) == group) else {
+            return false;
+        };
+        let board = self.to_board();
+        // Each round of the chase fills two points, so this bounds the search generously
+        let max_depth = <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE / 2;
+        ladder_captures(&board, prey_pos, max_depth)
+    }
+}
+
+/// The prey group at `prey_pos` is in atari and it's the prey's turn.
+fn ladder_captures<BS: BoardSize>(board: &Board<BS>, prey_pos: Pos<BS>, depth: usize) -> bool
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    if depth == 0 {
+        return false;
+    }
+    let prey = board[prey_pos].expect("Prey must be a stone");
+    let hunter = prey.other_player();
+
+    // Capturing a surrounding stone gives the prey a liberty and breaks the ladder
+    let prey_stones = board.flood_fill(prey_pos, |cell| cell == Some(prey));
+    let can_capture_hunter = prey_stones.iter().any(|stone| {
+        stone
+            .neighbors()
+            .filter(|neighbor| board[*neighbor] == Some(hunter))
+            .any(|neighbor| board.liberties_of_group_at(neighbor) == Some(NumStones::ONE))
+    });
+    if can_capture_hunter {
+        return false;
+    }
+
+    let [escape] = board.liberty_points_of_group_at(prey_pos)[..] else {
+        return false;
+    };
+    let mut board = *board;
+    if board.place_and_capture(escape, prey).is_err() {
+        // Extending would be suicide
+        return true;
+    }
+    let prey_liberties = board.liberty_points_of_group_at(prey_pos);
+    match prey_liberties.len() {
+        0 | 1 => true,
+        2 => prey_liberties.iter().any(|atari| {
+            let mut board = board;
+            board.place_and_capture(*atari, hunter).is_ok()
+                && board.liberties_of_group_at(prey_pos) == Some(NumStones::ONE)
+                && ladder_captures(&board, prey_pos, depth - 1)
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardSize9x9;
+
+    use super::*;
+
+    fn is_laddered(board: &str, prey: Pos<BoardSize9x9>) -> bool {
+        let board = Board::<BoardSize9x9>::from_str(board).unwrap();
+        let analysis = Analysis::analyze(&board);
+        analysis.is_laddered(analysis.group_at(prey))
+    }
+
+    #[test]
+    fn working_ladder() {
+        assert!(is_laddered(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ ○ ● ○ _ _ _
+            _ _ _ _ _ ○ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#,
+            Pos::from_xy(4, 4),
+        ));
+    }
+
+    #[test]
+    fn ladder_breaker() {
+        assert!(!is_laddered(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ ○ ● ○ _ _ _
+            _ _ _ _ _ ○ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ ● _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#,
+            Pos::from_xy(4, 4),
+        ));
+    }
+
+    #[test]
+    fn group_that_can_just_run_away() {
+        assert!(!is_laddered(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ ○ ● ○ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#,
+            Pos::from_xy(4, 4),
+        ));
+    }
+
+    #[test]
+    fn group_not_in_atari() {
+        assert!(!is_laddered(
+            r#"
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ ○ _ _ _ _
+            _ _ _ ○ ● _ _ _ _
+            _ _ _ _ _ ○ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#,
+            Pos::from_xy(4, 4),
+        ));
+    }
+}
@@ -5,16 +5,32 @@ mod board;
 mod game;
 mod gamelog;
 mod group_stones;
+mod komi;
+mod ladder;
+mod life_and_death;
+mod playout;
+mod scoring;
 mod sgf_parser;
 mod utils;
 
+pub use analysis::{Analysis, GroupInfo};
 pub use board::{
-    Board, BoardSize, BoardSize9x9, BoardSize13x13, BoardSize19x19, NumStones, PlaceStoneError,
-    Player, Pos,
+    Board, BoardBytesError, BoardParseError, BoardSize, BoardSize9x9, BoardSize13x13,
+    BoardSize19x19, BoardSize21x21, Direction, GtpVertexError, Notation, NumStones, Origin,
+    PlaceStoneError, Player, Pos, PosDisplay, StoneStyle,
 };
-pub use game::Game;
-pub use sgf_parser::{Move, Outcome, OutcomeMargin, SgfGame, parse_sgf};
-pub use utils::SmallSet;
+pub use game::{Game, GamePhase, GtpPlayError, KoRule, RepetitionKind};
+pub use gamelog::{GameLog, SyncError};
+pub use group_stones::GroupId;
+pub use komi::{Komi, KomiError};
+pub use life_and_death::GroupStatus;
+pub use playout::{PlayoutOptions, playout_to_end, random_move};
+pub use scoring::{Score, ScoreEstimate, ScoringRules};
+pub use sgf_parser::{
+    GameSummary, Move, Outcome, OutcomeMargin, SgfGame, SgfTree, SgfTreeNode, TimeSettings,
+    analyze_sgf_dir, parse_sgf, parse_sgf_file, parse_sgf_reader, parse_sgf_stream, parse_sgf_tree,
+};
+pub use utils::{Rng, SmallSet};
 
 #[cfg(test)]
 mod testutils;
@@ -0,0 +1,669 @@
+use crate::{Analysis, Board, BoardSize, GroupId, GroupInfo, Player, Pos, SmallSet};
+
+/// Result of reading out the life and death of a group, see [Analysis::status_of_group].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupStatus {
+    /// The owner can make two eyes whatever the opponent does
+    Alive,
+    /// The opponent can capture the group whatever the owner does
+    Dead,
+    /// Neither side could force a result within the search depth
+    Unsettled,
+}
+
+/// Number of moves the search looks ahead at most, including passes
+const MAX_DEPTH: usize = 8;
+
+/// Largest eye space that [Analysis::vital_points] knows shapes for
+const MAX_EYE_SPACE: usize = 6;
+
+/// Largest eye that [Analysis::is_seki] accepts, bigger ones might still make two eyes
+const MAX_SEKI_EYE: usize = 2;
+
+impl<BS: BoardSize> Analysis<BS>
+where
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    /// Reads out whether `group` lives when `to_move` plays next, using a depth-limited minimax
+    /// over the moves close to the group. The search is deepened one move at a time, so simple
+    /// positions are decided quickly.
+    ///
+    /// The group lives once it has two eyes (see [Board::is_eye]) and dies once it's captured.
+    /// Only the group's liberties and the empty points next to them are considered as moves,
+    /// and ko is ignored. Reading groups that are open to the rest of the board can take long,
+    /// since every liberty adds to the search.
+    ///
+    /// Panics if `group` is an empty region.
+    pub fn status_of_group(&self, group: GroupId<BS>, to_move: Player) -> GroupStatus {
+        let GroupInfo::PlayerGroup { owner, .. } = self.group_info(group) else {
+            panic!("Only groups of stones can live or die");
+        };
+        let anchor = Pos::all_positions()
+            .find(|pos| self.group_at(*pos) == group)
+            .expect("Every group has at least one stone");
+        let board = self.to_board();
+        for depth in 0..=MAX_DEPTH {
+            match solve(&board, anchor, to_move, depth, false) {
+                Some(winner) if winner == *owner => return GroupStatus::Alive,
+                Some(_) => return GroupStatus::Dead,
+                None => {}
+            }
+        }
+        GroupStatus::Unsettled
+    }
+
+    /// The vital points of `empty_region` if it's the eye space of a single player: the points
+    /// the owner needs to play to make two eyes, and the opponent to prevent them.
+    ///
+    /// The killing shapes (nakade) have a single vital point: straight and bent three, pyramid four,
+    /// bulky five, crossed five and rabbity six. Straight, bent and zigzag four have two vital points
+    /// which are miai, the owner makes two eyes with whichever one the opponent doesn't take.
+    /// Empty for regions bordered by both players or larger than six points, and for shapes that
+    /// can't make two eyes at all (one or two points, square four) or have room to spare.
+    ///
+    /// Panics if `empty_region` is a group of stones.
+    pub fn vital_points(&self, empty_region: GroupId<BS>) -> SmallSet<[Pos<BS>; 2]> {
+        assert!(
+            matches!(self.group_info(empty_region), GroupInfo::EmptyStonesGroup),
+            "Only empty regions have vital points"
+        );
+        let cells: Vec<Pos<BS>> = Pos::all_positions()
+            .filter(|pos| self.group_at(*pos) == empty_region)
+            .collect();
+        let owners: SmallSet<[Player; 2]> = self
+            .adjacent_groups(empty_region)
+            .iter()
+            .filter_map(|group| match self.group_info(*group) {
+                GroupInfo::PlayerGroup { owner, .. } => Some(*owner),
+                GroupInfo::EmptyStonesGroup => None,
+            })
+            .collect();
+        if cells.len() > MAX_EYE_SPACE || owners.len() != 1 {
+            return SmallSet::new();
+        }
+
+        // The shapes are told apart by how many neighbors each point has inside the region
+        let degree = |pos: Pos<BS>| {
+            pos.neighbors()
+                .filter(|neighbor| self.group_at(*neighbor) == empty_region)
+                .count()
+        };
+        let with_degree = |wanted: usize| {
+            cells
+                .iter()
+                .copied()
+                .filter(|pos| degree(*pos) == wanted)
+                .collect()
+        };
+        let mut degrees: Vec<usize> = cells.iter().map(|pos| degree(*pos)).collect();
+        degrees.sort();
+        match degrees[..] {
+            // Straight or bent three
+            [1, 1, 2] => with_degree(2),
+            // Pyramid four
+            [1, 1, 1, 3] => with_degree(3),
+            // Straight, bent or zigzag four
+            [1, 1, 2, 2] => with_degree(2),
+            // Bulky five
+            [1, 2, 2, 2, 3] => with_degree(3),
+            // Crossed five
+            [1, 1, 1, 1, 4] => with_degree(4),
+            // Rabbity six
+            [1, 1, 2, 2, 2, 4] => with_degree(4),
+            _ => SmallSet::new(),
+        }
+    }
+
+    /// Whether the enemy groups `group_a` and `group_b` live in seki: they share liberties, and
+    /// whoever fills one of them puts their own group in atari, so neither can capture the other.
+    ///
+    /// This only covers simple seki. Either both groups have no liberties except shared ones, and at
+    /// least two of those, or each group has exactly one small eye (one or two points) in addition to
+    /// the shared liberties. Filling any shared liberty must leave the filler with at most one
+    /// liberty, so it's not seki if that move connects to other stones with liberties elsewhere.
+    /// Seki involving more than two groups isn't recognized.
+    ///
+    /// Panics if one of the groups is an empty region.
+    pub fn is_seki(&self, group_a: GroupId<BS>, group_b: GroupId<BS>) -> bool {
+        let owner = |group| match self.group_info(group) {
+            GroupInfo::PlayerGroup { owner, .. } => *owner,
+            GroupInfo::EmptyStonesGroup => panic!("Only groups of stones can be in seki"),
+        };
+        let (owner_a, owner_b) = (owner(group_a), owner(group_b));
+        if owner_a == owner_b {
+            return false;
+        }
+        let liberties_a = self._liberty_points(group_a);
+        let liberties_b = self._liberty_points(group_b);
+        let shared: Vec<Pos<BS>> = liberties_a
+            .iter()
+            .copied()
+            .filter(|pos| liberties_b.contains(pos))
+            .collect();
+        if shared.is_empty() {
+            return false;
+        }
+        let num_eyes_a = self._num_seki_eyes(&liberties_a, &liberties_b, owner_a);
+        let num_eyes_b = self._num_seki_eyes(&liberties_b, &liberties_a, owner_b);
+        let enough_liberties = match (num_eyes_a, num_eyes_b) {
+            (Some(0), Some(0)) => shared.len() >= 2,
+            (Some(1), Some(1)) => true,
+            _ => false,
+        };
+        enough_liberties && self._filling_is_self_atari(&shared)
+    }
+
+    /// Whether each player filling any of the `shared` liberties is suicide or leaves the new stone's
+    /// group with a single liberty and without capturing anything
+    fn _filling_is_self_atari(&self, shared: &[Pos<BS>]) -> bool {
+        let board = self.to_board();
+        shared.iter().all(|pos| {
+            [Player::Black, Player::White].into_iter().all(|player| {
+                let mut board = board;
+                match board.place_and_capture(*pos, player) {
+                    Ok(captured) => {
+                        captured.len() == 0
+                            && board
+                                .liberties_of_group_at(*pos)
+                                .is_some_and(|liberties| liberties.into_usize() <= 1)
+                    }
+                    Err(_) => true,
+                }
+            })
+        })
+    }
+
+    /// Number of true eyes (see [Board::is_eye]) of `group`, counting only eyes whose neighbors all
+    /// belong to `group` itself. A group with two or more of them is alive.
+    ///
+    /// Panics if `group` is an empty region.
+    pub fn count_eyes(&self, group: GroupId<BS>) -> usize {
+        let GroupInfo::PlayerGroup { owner, .. } = self.group_info(group) else {
+            panic!("Only groups of stones have eyes");
+        };
+        let board = self.to_board();
+        self._liberty_points(group)
+            .into_iter()
+            .filter(|liberty| {
+                board.is_eye(*liberty, *owner)
+                    && liberty
+                        .neighbors()
+                        .all(|neighbor| self.group_at(neighbor) == group)
+            })
+            .count()
+    }
+
+    fn _liberty_points(&self, group: GroupId<BS>) -> Vec<Pos<BS>> {
+        let mut liberties = Vec::new();
+        for pos in Pos::all_positions().filter(|pos| self.group_at(*pos) == group) {
+            for neighbor in pos.neighbors() {
+                let is_empty = matches!(
+                    self.group_info(self.group_at(neighbor)),
+                    GroupInfo::EmptyStonesGroup
+                );
+                if is_empty && !liberties.contains(&neighbor) {
+                    liberties.push(neighbor);
+                }
+            }
+        }
+        liberties
+    }
+
+    /// How many eyes the liberties that aren't `shared` form, or `None` if some of them aren't
+    /// part of a small eye bordered only by `owner`'s stones
+    fn _num_seki_eyes(
+        &self,
+        liberties: &[Pos<BS>],
+        shared: &[Pos<BS>],
+        owner: Player,
+    ) -> Option<usize> {
+        let mut eyes = SmallSet::<[GroupId<BS>; 2]>::new();
+        for pos in liberties.iter().filter(|pos| !shared.contains(pos)) {
+            let region = self.group_at(*pos);
+            let only_bordered_by_owner = self.adjacent_groups(region).iter().all(|group| {
+                matches!(self.group_info(*group), GroupInfo::PlayerGroup { owner: o, .. } if *o == owner)
+            });
+            let size = Pos::all_positions()
+                .filter(|pos| self.group_at(*pos) == region)
+                .count();
+            if !only_bordered_by_owner || size > MAX_SEKI_EYE {
+                return None;
+            }
+            eyes.insert(region);
+        }
+        Some(eyes.len())
+    }
+}
+
+/// Who wins the fight over the group at `anchor` within `depth` moves: its owner if it makes two
+/// eyes, the opponent if it's captured, `None` if it's still open.
+fn solve<BS: BoardSize>(
+    board: &Board<BS>,
+    anchor: Pos<BS>,
+    to_move: Player,
+    depth: usize,
+    opponent_passed: bool,
+) -> Option<Player>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    let defender = board[anchor].expect("Captured groups aren't searched any further");
+    if num_eyes(board, anchor, defender) >= 2 {
+        return Some(defender);
+    }
+    if depth == 0 {
+        return None;
+    }
+
+    let mut is_open = false;
+    for pos in candidate_moves(board, anchor) {
+        // Filling your own eye never helps the group
+        if to_move == defender && board.is_eye(pos, defender) {
+            continue;
+        }
+        let mut next = *board;
+        if next.place_and_capture(pos, to_move).is_err() {
+            continue;
+        }
+        let result = if next[anchor] == Some(defender) {
+            solve(&next, anchor, to_move.other_player(), depth - 1, false)
+        } else {
+            // The group was captured
+            Some(to_move)
+        };
+        match result {
+            Some(winner) if winner == to_move => return result,
+            Some(_) => {}
+            None => is_open = true,
+        }
+    }
+
+    // Two passes in a row end the fight without a result
+    if !opponent_passed {
+        match solve(board, anchor, to_move.other_player(), depth - 1, true) {
+            Some(winner) if winner == to_move => return Some(winner),
+            Some(_) => {}
+            None => is_open = true,
+        }
+    } else {
+        is_open = true;
+    }
+
+    if is_open {
+        None
+    } else {
+        Some(to_move.other_player())
+    }
+}
+
+/// Empty points next to the group that are eyes of its owner
+fn num_eyes<BS: BoardSize>(board: &Board<BS>, anchor: Pos<BS>, owner: Player) -> usize
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    board
+        .liberty_points_of_group_at(anchor)
+        .into_iter()
+        .filter(|liberty| board.is_eye(*liberty, owner))
+        .count()
+}
+
+/// Liberties of the group and the empty points next to them, sorted by index
+fn candidate_moves<BS: BoardSize>(board: &Board<BS>, anchor: Pos<BS>) -> Vec<Pos<BS>>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    let liberties = board.liberty_points_of_group_at(anchor);
+    let mut candidates: Vec<Pos<BS>> = liberties
+        .iter()
+        .flat_map(|liberty| liberty.neighbors())
+        .filter(|neighbor| board[*neighbor].is_none())
+        .chain(liberties.iter().copied())
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardSize9x9;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn status(board: &str, group: Pos<BoardSize9x9>, to_move: Player) -> GroupStatus {
+        let board = Board::<BoardSize9x9>::from_str(board).unwrap();
+        let analysis = Analysis::analyze(&board);
+        analysis.status_of_group(analysis.group_at(group), to_move)
+    }
+
+    // White has a two point eye space in the corner, which can only make one eye
+    const DEAD_IN_THE_CORNER: &str = r#"
+        _ _ ● ○ _ _ _ _ _
+        ● ● ● ○ _ _ _ _ _
+        ○ ○ ○ ○ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        "#;
+
+    const TWO_EYES: &str = r#"
+        _ ● _ ● ○ _ _ _ _
+        ● ● ● ● ○ _ _ _ _
+        ○ ○ ○ ○ ○ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        "#;
+
+    // Whoever plays the middle of the three point eye space first decides the fight
+    const STRAIGHT_THREE: &str = r#"
+        _ _ _ ● ○ _ _ _ _
+        ● ● ● ● ○ _ _ _ _
+        ○ ○ ○ ○ ○ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        "#;
+
+    #[test]
+    fn dead_in_the_corner() {
+        for to_move in [Player::Black, Player::White] {
+            assert_eq!(
+                GroupStatus::Dead,
+                status(DEAD_IN_THE_CORNER, Pos::from_xy(0, 1), to_move)
+            );
+        }
+    }
+
+    #[test]
+    fn two_eyes_are_alive() {
+        for to_move in [Player::Black, Player::White] {
+            assert_eq!(
+                GroupStatus::Alive,
+                status(TWO_EYES, Pos::from_xy(0, 1), to_move)
+            );
+        }
+    }
+
+    #[test]
+    fn straight_three_depends_on_who_moves() {
+        assert_eq!(
+            GroupStatus::Alive,
+            status(STRAIGHT_THREE, Pos::from_xy(0, 1), Player::White)
+        );
+        assert_eq!(
+            GroupStatus::Dead,
+            status(STRAIGHT_THREE, Pos::from_xy(0, 1), Player::Black)
+        );
+    }
+
+    fn count_eyes(board: &str, group: Pos<BoardSize9x9>) -> usize {
+        let board = Board::<BoardSize9x9>::from_str(board).unwrap();
+        let analysis = Analysis::analyze(&board);
+        analysis.count_eyes(analysis.group_at(group))
+    }
+
+    #[test]
+    fn count_two_eyes() {
+        assert_eq!(2, count_eyes(TWO_EYES, Pos::from_xy(0, 1)));
+        // The surrounding black group has no eyes
+        assert_eq!(0, count_eyes(TWO_EYES, Pos::from_xy(0, 2)));
+    }
+
+    #[test]
+    fn count_one_eye() {
+        let board = r#"
+            _ ● ● ● ○ _ _ _ _
+            ● ● ● ● ○ _ _ _ _
+            ○ ○ ○ ○ ○ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(1, count_eyes(board, Pos::from_xy(0, 1)));
+        // Neither point of the two point eye space is an eye on its own
+        assert_eq!(0, count_eyes(DEAD_IN_THE_CORNER, Pos::from_xy(0, 1)));
+    }
+
+    #[test]
+    fn eyes_shared_with_another_group_dont_count() {
+        // The corner point is an eye of White, but it's bordered by two separate white groups
+        let board = r#"
+            _ ● ○ _ _ _ _ _ _
+            ● _ ○ _ _ _ _ _ _
+            ○ ○ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(0, count_eyes(board, Pos::from_xy(1, 0)));
+        assert_eq!(0, count_eyes(board, Pos::from_xy(0, 1)));
+    }
+
+    fn is_seki(board: &str, group_a: Pos<BoardSize9x9>, group_b: Pos<BoardSize9x9>) -> bool {
+        let board = Board::<BoardSize9x9>::from_str(board).unwrap();
+        let analysis = Analysis::analyze(&board);
+        analysis.is_seki(analysis.group_at(group_a), analysis.group_at(group_b))
+    }
+
+    // The white group in the middle and the black group around it share the two empty points and
+    // have no other liberties. No other stones border the shared points, and the outer white group
+    // has plenty of liberties.
+    const SEKI_WITHOUT_EYES: &str = r#"
+        ○ ○ ○ ○ ○ ○ ○ ● _
+        ○ _ ● ● ● _ ○ ● _
+        ○ ○ ○ ○ ○ ○ ○ ● _
+        ● ● ● ● ● ● ● ● _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        _ _ _ _ _ _ _ _ _
+        "#;
+
+    #[test]
+    fn seki_without_eyes() {
+        let black = Pos::from_xy(0, 0);
+        let white = Pos::from_xy(2, 1);
+        assert!(is_seki(SEKI_WITHOUT_EYES, black, white));
+        assert!(is_seki(SEKI_WITHOUT_EYES, white, black));
+        assert!(!is_seki(SEKI_WITHOUT_EYES, black, Pos::from_xy(7, 0)));
+        // Groups of the same player are never in seki with each other
+        assert!(!is_seki(SEKI_WITHOUT_EYES, white, Pos::from_xy(7, 0)));
+    }
+
+    #[test]
+    fn no_seki_if_filling_connects_to_the_outside() {
+        // Both groups only have the shared liberties, but White filling one of them connects to
+        // the outer white stones, and then Black filling the other one would be suicide
+        let board = r#"
+            _ _ _ _ _ _ _ _ _
+            _ ● ● ● ● ● _ _ _
+            ● ○ ○ ○ ○ ○ ● _ _
+            ● _ ● ● ● _ ● _ _
+            ● ○ ○ ○ ○ ○ ● _ _
+            _ ● ● ● ● ● _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        let white = Pos::from_xy(2, 3);
+        assert!(!is_seki(board, Pos::from_xy(1, 2), white));
+        assert!(!is_seki(board, white, Pos::from_xy(1, 4)));
+    }
+
+    #[test]
+    fn seki_with_one_eye_each() {
+        let board = r#"
+            _ ○ _ ● _ ● ○ _ _
+            ○ ○ ● ● ● ● ○ _ _
+            ● ● ● ○ ○ ○ ○ _ _
+            ○ ○ ○ ○ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert!(is_seki(board, Pos::from_xy(1, 0), Pos::from_xy(3, 0)));
+    }
+
+    #[test]
+    fn no_seki_with_a_single_shared_liberty() {
+        // White filled one of the shared liberties, so either side can capture by playing the other one
+        let board = r#"
+            _ _ _ _ _ _ _ _ _
+            _ ● ● ● ● ● _ _ _
+            ● ○ ○ ○ ○ ○ ● _ _
+            ● _ ● ● ● ● ● _ _
+            ● ○ ○ ○ ○ ○ ● _ _
+            _ ● ● ● ● ● _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert!(!is_seki(board, Pos::from_xy(1, 2), Pos::from_xy(2, 3)));
+    }
+
+    fn vital_points(board: &str, region: Pos<BoardSize9x9>) -> Vec<Pos<BoardSize9x9>> {
+        let board = Board::<BoardSize9x9>::from_str(board).unwrap();
+        let analysis = Analysis::analyze(&board);
+        let mut points: Vec<_> = analysis
+            .vital_points(analysis.group_at(region))
+            .iter()
+            .copied()
+            .collect();
+        points.sort();
+        points
+    }
+
+    #[test]
+    fn vital_point_of_straight_three() {
+        assert_eq!(
+            vec![Pos::from_xy(1, 0)],
+            vital_points(STRAIGHT_THREE, Pos::from_xy(0, 0))
+        );
+    }
+
+    #[test]
+    fn vital_point_of_bent_three() {
+        let board = r#"
+            _ _ ● ○ _ _ _ _ _
+            _ ● ● ○ _ _ _ _ _
+            ● ● ○ ○ _ _ _ _ _
+            ○ ○ ○ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(
+            vec![Pos::from_xy(0, 0)],
+            vital_points(board, Pos::from_xy(0, 1))
+        );
+    }
+
+    #[test]
+    fn vital_points_of_bent_four_are_miai() {
+        let board = r#"
+            _ _ ● ○ _ _ _ _ _
+            _ ● ● ○ _ _ _ _ _
+            _ ● ○ ○ _ _ _ _ _
+            ● ● ○ _ _ _ _ _ _
+            ○ ○ ○ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(
+            vec![Pos::from_xy(0, 0), Pos::from_xy(0, 1)],
+            vital_points(board, Pos::from_xy(1, 0))
+        );
+    }
+
+    #[test]
+    fn vital_point_of_crossed_five() {
+        let board = r#"
+            _ _ ● _ _ _ _ _ _
+            _ ● _ ● _ _ _ _ _
+            ● _ _ _ ● _ _ _ _
+            _ ● _ ● _ _ _ _ _
+            _ _ ● _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(
+            vec![Pos::from_xy(2, 2)],
+            vital_points(board, Pos::from_xy(2, 2))
+        );
+    }
+
+    #[test]
+    fn no_vital_points() {
+        // Square four can't make two eyes anyway
+        let square_four = r#"
+            _ _ ● ○ _ _ _ _ _
+            _ _ ● ○ _ _ _ _ _
+            ● ● ● ○ _ _ _ _ _
+            ○ ○ ○ ○ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(
+            Vec::<Pos<BoardSize9x9>>::new(),
+            vital_points(square_four, Pos::from_xy(0, 0))
+        );
+        // Bordered by both players, so it's no eye space
+        let shared = r#"
+            _ _ _ ○ _ _ _ _ _
+            ● ● ● ○ _ _ _ _ _
+            ○ ○ ○ ○ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            _ _ _ _ _ _ _ _ _
+            "#;
+        assert_eq!(
+            Vec::<Pos<BoardSize9x9>>::new(),
+            vital_points(shared, Pos::from_xy(0, 0))
+        );
+        // The rest of the board is too large
+        assert_eq!(
+            Vec::<Pos<BoardSize9x9>>::new(),
+            vital_points(STRAIGHT_THREE, Pos::from_xy(8, 8))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only groups of stones can live or die")]
+    fn empty_region_panics() {
+        status(TWO_EYES, Pos::from_xy(8, 8), Player::Black);
+    }
+}
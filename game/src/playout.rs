@@ -0,0 +1,104 @@
+use crate::{BoardSize, Game, Pos, utils::Rng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayoutOptions {
+    /// Whether players may fill their own eyes. Filling a real eye is almost always a mistake
+    /// and can keep a playout from ever ending, so this is off by default.
+    pub allow_eye_fill: bool,
+    /// Stop the playout after this many moves, even if it isn't over yet (e.g. because of ko fights)
+    pub max_moves: usize,
+}
+
+impl Default for PlayoutOptions {
+    fn default() -> Self {
+        Self {
+            allow_eye_fill: false,
+            max_moves: 1000,
+        }
+    }
+}
+
+/// Picks a uniformly random legal move for the current player, or `None` if they should pass.
+pub fn random_move<BS: BoardSize>(
+    game: &Game<BS>,
+    rng: &mut Rng,
+    options: &PlayoutOptions,
+) -> Option<Pos<BS>>
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    let player = game.current_player();
+    let candidates: Vec<Pos<BS>> = game
+        .legal_moves()
+        .filter(|pos| options.allow_eye_fill || !game.is_eye_fill(*pos, player))
+        .collect();
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates[rng.below(candidates.len())])
+    }
+}
+
+/// Plays random moves until the game is over or `options.max_moves` is reached.
+pub fn playout_to_end<BS: BoardSize>(game: &mut Game<BS>, rng: &mut Rng, options: &PlayoutOptions)
+where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+    [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+{
+    for _ in 0..options.max_moves {
+        if game.is_game_over() {
+            return;
+        }
+        match random_move(game, rng, options) {
+            Some(pos) => game
+                .place_stone(pos)
+                .expect("random_move only returns legal moves"),
+            None => game.pass_turn(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use enum_map::enum_map;
+
+    use crate::{
+        Board, NumStones, Player,
+        board::{BoardSize5x5, BoardSize9x9},
+    };
+
+    use super::*;
+
+    #[test]
+    fn playout_on_empty_board_ends() {
+        let mut game = Game::<BoardSize9x9>::new();
+        playout_to_end(&mut game, &mut Rng::new(1), &PlayoutOptions::default());
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn two_eyed_group_is_never_self_filled() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ○ _ ○ ●
+            ○ ○ ○ ○ ●
+            ● ● ● ● ●
+            _ _ _ _ _
+            _ _ _ _ _
+            "#,
+        )
+        .unwrap();
+        let eyes = [Pos::from_xy(0, 0), Pos::from_xy(2, 0)];
+        for seed in 0..20 {
+            let mut game =
+                Game::from_board(board, Player::Black, enum_map! { _ => NumStones::ZERO });
+            playout_to_end(&mut game, &mut Rng::new(seed), &PlayoutOptions::default());
+            assert!(game.is_game_over());
+            for eye in eyes {
+                assert_eq!(None, game.board()[eye], "Seed {seed} filled an eye");
+            }
+            assert_eq!(Some(Player::Black), game.board()[Pos::from_xy(1, 0)]);
+        }
+    }
+}
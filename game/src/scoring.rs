@@ -0,0 +1,376 @@
+use derive_where::derive_where;
+use enum_map::{EnumMap, enum_map};
+
+use crate::{
+    Board, BoardSize, Komi, NumStones, Outcome, OutcomeMargin, Player, Pos, analysis::Analysis,
+};
+
+/// How points are counted at the end of the game. Both usually agree on the winner,
+/// but the margin can differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringRules {
+    /// Chinese rules: stones on the board plus territory
+    #[default]
+    Area,
+    /// Japanese rules: territory plus prisoners
+    Territory,
+    /// Area scoring for rule sets that require the neutral points (dame) to be filled before
+    /// counting. Each dame is counted as a stone of whoever would fill it when the players take
+    /// turns, starting with the player to move, whether or not they were actually played out.
+    AreaWithDameFilled,
+}
+
+/// Count of a finished position: the stones, surrounded territory and prisoners of each player.
+/// An empty point is territory of a player if it only borders their stones.
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score<BS: BoardSize> {
+    stones: EnumMap<Player, NumStones<BS>>,
+    territory: EnumMap<Player, NumStones<BS>>,
+    prisoners: EnumMap<Player, NumStones<BS>>,
+    dame: NumStones<BS>,
+    to_move: Player,
+}
+
+impl<BS: BoardSize> Score<BS> {
+    /// Counts the board after removing `dead_stones` from it. Dead stones count as prisoners
+    /// of the opponent, in addition to the stones `captured_by` each player during the game.
+    /// `to_move` is the player who would fill the first dame for [ScoringRules::AreaWithDameFilled].
+    pub(crate) fn count(
+        board: &Board<BS>,
+        dead_stones: &[Pos<BS>],
+        captured_by: EnumMap<Player, NumStones<BS>>,
+        to_move: Player,
+    ) -> Self
+    where
+        [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+        [(); <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE]:,
+    {
+        let mut board = *board;
+        let mut prisoners = captured_by;
+        for pos in dead_stones {
+            if let Some(owner) = board[*pos] {
+                prisoners[owner.other_player()] += NumStones::ONE;
+            }
+            board.set(*pos, None);
+        }
+        let analysis = Analysis::analyze(&board);
+
+        // Single pass over the board: count stones, and for each empty region its size and which
+        // players' stones it touches. Regions are then attributed as a whole.
+        let mut stones = enum_map! { _ => NumStones::ZERO };
+        let mut regions =
+            vec![(NumStones::ZERO, enum_map! { _ => false }); analysis.groups().len()];
+        for pos in Pos::all_positions() {
+            match board[pos] {
+                Some(owner) => stones[owner] += NumStones::ONE,
+                None => {
+                    let (size, borders) = &mut regions[analysis.group_at(pos).into_usize()];
+                    *size += NumStones::ONE;
+                    for neighbor in pos.neighbors() {
+                        if let Some(owner) = board[neighbor] {
+                            borders[owner] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut territory = enum_map! { _ => NumStones::ZERO };
+        let mut dame = NumStones::ZERO;
+        for (size, borders) in regions {
+            match (borders[Player::Black], borders[Player::White]) {
+                (true, false) => territory[Player::Black] += size,
+                (false, true) => territory[Player::White] += size,
+                (true, true) => dame += size,
+                // Stone groups, or a board without any stones
+                (false, false) => {}
+            }
+        }
+
+        Self {
+            stones,
+            territory,
+            prisoners,
+            dame,
+            to_move,
+        }
+    }
+
+    pub fn stones(&self, player: Player) -> NumStones<BS> {
+        self.stones[player]
+    }
+
+    pub fn territory(&self, player: Player) -> NumStones<BS> {
+        self.territory[player]
+    }
+
+    /// Opponent stones captured by `player` during the game or removed as dead at the end
+    pub fn prisoners(&self, player: Player) -> NumStones<BS> {
+        self.prisoners[player]
+    }
+
+    /// Empty points that border stones of both players and count for neither
+    pub fn dame(&self) -> NumStones<BS> {
+        self.dame
+    }
+
+    pub fn points(&self, rules: ScoringRules, player: Player) -> NumStones<BS> {
+        match rules {
+            ScoringRules::Area => self.stones[player] + self.territory[player],
+            ScoringRules::Territory => self.territory[player] + self.prisoners[player],
+            ScoringRules::AreaWithDameFilled => {
+                self.stones[player] + self.territory[player] + self._filled_dame(player)
+            }
+        }
+    }
+
+    /// Dame filled by `player` when both take turns filling them, starting with [Self::to_move].
+    /// With an odd number of dame, that player fills one more.
+    fn _filled_dame(&self, player: Player) -> NumStones<BS> {
+        let dame = self.dame.into_usize();
+        if player == self.to_move {
+            NumStones::from_usize(dame.div_ceil(2))
+        } else {
+            NumStones::from_usize(dame / 2)
+        }
+    }
+
+    pub fn outcome(&self, rules: ScoringRules, komi: Komi) -> Outcome {
+        let black_times_two = 2 * self.points(rules, Player::Black).into_usize() as i64;
+        let white_times_two = 2 * self.points(rules, Player::White).into_usize() as i64
+            + i64::from(komi.points_times_two());
+        let (winner, points_times_two) = match black_times_two.cmp(&white_times_two) {
+            std::cmp::Ordering::Equal => return Outcome::Draw,
+            std::cmp::Ordering::Greater => (Player::Black, black_times_two - white_times_two),
+            std::cmp::Ordering::Less => (Player::White, white_times_two - black_times_two),
+        };
+        Outcome::WithWinner {
+            winner,
+            margin: OutcomeMargin::ByPoints {
+                points_times_two: points_times_two as u32,
+            },
+        }
+    }
+}
+
+/// Running estimate of a game that is still being played. Empty regions bordered by only one color
+/// count as its territory and contested regions are neutral, like in [Score], but no stones are
+/// considered dead. Points are counted by area.
+#[derive_where(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreEstimate<BS: BoardSize> {
+    score: Score<BS>,
+    komi: Komi,
+}
+
+impl<BS: BoardSize> ScoreEstimate<BS> {
+    pub(crate) fn new(score: Score<BS>, komi: Komi) -> Self {
+        Self { score, komi }
+    }
+
+    pub fn stones(&self, player: Player) -> NumStones<BS> {
+        self.score.stones(player)
+    }
+
+    pub fn territory(&self, player: Player) -> NumStones<BS> {
+        self.score.territory(player)
+    }
+
+    pub fn points(&self, player: Player) -> NumStones<BS> {
+        self.score.points(ScoringRules::Area, player)
+    }
+
+    /// Who would win if the game was counted now
+    pub fn outcome(&self) -> Outcome {
+        self.score.outcome(ScoringRules::Area, self.komi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardSize5x5;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const FINISHED_BOARD: &str = r#"
+        _ ○ ● _ _
+        ○ ○ ● _ ●
+        _ ○ ● ● _
+        ○ ○ _ ● ●
+        _ ○ ● _ _
+    "#;
+
+    const NO_CAPTURES: EnumMap<Player, NumStones<BoardSize5x5>> =
+        EnumMap::from_array([NumStones::ZERO; 2]);
+
+    #[test]
+    fn empty_board() {
+        let score = Score::count(
+            &Board::<BoardSize5x5>::new(),
+            &[],
+            NO_CAPTURES,
+            Player::Black,
+        );
+        for player in [Player::Black, Player::White] {
+            assert_eq!(NumStones::ZERO, score.stones(player));
+            assert_eq!(NumStones::ZERO, score.territory(player));
+        }
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 15
+                },
+            },
+            score.outcome(ScoringRules::Area, Komi::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn finished_position() {
+        let board = Board::<BoardSize5x5>::from_str(FINISHED_BOARD).unwrap();
+        let score = Score::count(&board, &[], NO_CAPTURES, Player::Black);
+        assert_eq!(NumStones::from_usize(7), score.stones(Player::Black));
+        assert_eq!(NumStones::from_usize(3), score.territory(Player::Black));
+        assert_eq!(NumStones::from_usize(8), score.stones(Player::White));
+        // (2, 3) touches both colors and is neutral
+        assert_eq!(NumStones::from_usize(6), score.territory(Player::White));
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 9,
+                },
+            },
+            score.outcome(ScoringRules::Area, Komi::new(0.5).unwrap())
+        );
+    }
+
+    #[test]
+    fn dame_filled() {
+        let board = Board::<BoardSize5x5>::from_str(FINISHED_BOARD).unwrap();
+        let komi = Komi::new(0.5).unwrap();
+        let white_wins_by = |points_times_two| Outcome::WithWinner {
+            winner: Player::White,
+            margin: OutcomeMargin::ByPoints { points_times_two },
+        };
+
+        // (2, 3) is the only dame
+        let score = Score::count(&board, &[], NO_CAPTURES, Player::Black);
+        assert_eq!(NumStones::ONE, score.dame());
+        assert_eq!(
+            NumStones::from_usize(10),
+            score.points(ScoringRules::Area, Player::Black)
+        );
+        assert_eq!(
+            NumStones::from_usize(11),
+            score.points(ScoringRules::AreaWithDameFilled, Player::Black)
+        );
+        assert_eq!(
+            NumStones::from_usize(14),
+            score.points(ScoringRules::AreaWithDameFilled, Player::White)
+        );
+        assert_eq!(white_wins_by(9), score.outcome(ScoringRules::Area, komi));
+        assert_eq!(
+            white_wins_by(7),
+            score.outcome(ScoringRules::AreaWithDameFilled, komi)
+        );
+
+        // With White to move, White fills the dame
+        let score = Score::count(&board, &[], NO_CAPTURES, Player::White);
+        assert_eq!(
+            white_wins_by(11),
+            score.outcome(ScoringRules::AreaWithDameFilled, komi)
+        );
+    }
+
+    #[test]
+    fn draw() {
+        let board = Board::<BoardSize5x5>::from_str(FINISHED_BOARD).unwrap();
+        let score = Score::count(&board, &[], NO_CAPTURES, Player::Black);
+        assert_eq!(
+            Outcome::Draw,
+            score.outcome(ScoringRules::Area, Komi::new(-4.0).unwrap())
+        );
+    }
+
+    #[test]
+    fn dead_stones_are_removed() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ○ ● _ _
+            ○ ○ ● _ ●
+            _ ○ ● ● _
+            ○ ○ _ ● ○
+            _ ○ ● _ _
+        "#,
+        )
+        .unwrap();
+        let score = Score::count(&board, &[Pos::from_xy(4, 3)], NO_CAPTURES, Player::Black);
+        assert_eq!(NumStones::from_usize(7), score.stones(Player::Black));
+        assert_eq!(NumStones::from_usize(3), score.territory(Player::Black));
+        assert_eq!(NumStones::from_usize(7), score.stones(Player::White));
+        assert_eq!(NumStones::from_usize(7), score.territory(Player::White));
+    }
+
+    #[test]
+    fn area_and_territory_scoring() {
+        let board = Board::<BoardSize5x5>::from_str(
+            r#"
+            _ ○ ● _ _
+            ○ ○ ● _ ●
+            _ ○ ● ● _
+            ○ ○ _ ● ○
+            _ ○ ● _ _
+        "#,
+        )
+        .unwrap();
+        let captured_by = enum_map! {
+            Player::Black => NumStones::from_usize(2),
+            Player::White => NumStones::ZERO,
+        };
+        let score = Score::count(&board, &[Pos::from_xy(4, 3)], captured_by, Player::Black);
+        assert_eq!(NumStones::from_usize(2), score.prisoners(Player::Black));
+        assert_eq!(NumStones::from_usize(1), score.prisoners(Player::White));
+
+        // Area: 7 + 3 for Black, 7 + 7 for White
+        assert_eq!(
+            NumStones::from_usize(10),
+            score.points(ScoringRules::Area, Player::Black)
+        );
+        assert_eq!(
+            NumStones::from_usize(14),
+            score.points(ScoringRules::Area, Player::White)
+        );
+        // Territory: 3 + 2 for Black, 7 + 1 for White
+        assert_eq!(
+            NumStones::from_usize(5),
+            score.points(ScoringRules::Territory, Player::Black)
+        );
+        assert_eq!(
+            NumStones::from_usize(8),
+            score.points(ScoringRules::Territory, Player::White)
+        );
+
+        // Same winner, different margins
+        let komi = Komi::new(6.5).unwrap();
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 21,
+                },
+            },
+            score.outcome(ScoringRules::Area, komi)
+        );
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 19,
+                },
+            },
+            score.outcome(ScoringRules::Territory, komi)
+        );
+    }
+}
@@ -1,33 +1,87 @@
 use anyhow::{Context, Result, anyhow, bail, ensure};
-use sgf_parse::go::Prop;
+use enum_map::enum_map;
+use sgf_parse::{
+    SgfNode,
+    go::{Point, Prop},
+};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufReader, Bytes, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use crate::{BoardSize19x19, Game, Player, Pos};
+use crate::{Board, BoardSize19x19, Game, NumStones, Player, Pos};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SgfGame {
     // TODO In our integration tests, test that we're getting to the same outcome if the outcome is by points.
     pub outcome: Outcome,
+    /// Position set up with the AB, AW and AE properties before the first move
+    pub initial_board: Board<BoardSize19x19>,
     pub moves: Vec<Move>,
+    /// Clock settings from the TM and OT properties, `None` if the file has neither. TM values that
+    /// aren't a non-negative number of seconds, like the "9h" some older files use, are ignored.
+    pub time_settings: Option<TimeSettings>,
+    /// Time the moving player had left after each move, from the BL and WL properties.
+    /// Same length as [Self::moves], with `None` for moves that don't record it. Negative values,
+    /// which some servers write when a player loses on time, are stored as zero.
+    pub time_left: Vec<Option<Duration>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeSettings {
+    /// Main time per player, from the TM property
+    pub main_time: Option<Duration>,
+    /// Overtime system as free text, e.g. "5x30 byo-yomi", from the OT property
+    pub overtime: Option<String>,
 }
 
 impl SgfGame {
+    /// Replays the first `move_index` moves. Fails if one of them is illegal, e.g. because
+    /// the game was already over after both players passed.
     pub fn game_position_after_num_moves(&self, move_index: usize) -> Result<Game<BoardSize19x19>> {
-        let mut game = Game::new();
-        let mut moves = self.moves.iter();
-        for i in 0..move_index {
-            match moves.next() {
-                None => panic!("Expected {move_index} moves but only saw {i}"),
-                Some(Move::Pass) => {
-                    game.pass_turn();
-                }
-                Some(Move::Place { x, y }) => {
-                    game.place_stone(Pos::from_xy(usize::from(*x), usize::from(*y)))
-                        .unwrap();
-                }
-            }
+        ensure!(
+            move_index <= self.moves.len(),
+            "Expected {move_index} moves but the game only has {}",
+            self.moves.len()
+        );
+        let mut game = Game::from_board(
+            self.initial_board,
+            Player::Black,
+            enum_map! { _ => NumStones::ZERO },
+        );
+        for (i, move_) in self.moves[..move_index].iter().enumerate() {
+            game.play_move(move_)
+                .with_context(|| format!("Failed to replay move {i}: {move_:?}"))?;
         }
         Ok(game)
     }
+
+    /// Number of stones captured by (black, white) with the move at `move_index`.
+    pub fn captures_at(
+        &self,
+        move_index: usize,
+    ) -> Result<(NumStones<BoardSize19x19>, NumStones<BoardSize19x19>)> {
+        let move_ = self.moves.get(move_index).ok_or_else(|| {
+            anyhow!(
+                "Move index {move_index} out of range, the game only has {} moves",
+                self.moves.len()
+            )
+        })?;
+        let mut game = self.game_position_after_num_moves(move_index)?;
+        let before = (
+            game.num_captured_by(Player::Black),
+            game.num_captured_by(Player::White),
+        );
+        game.play_move(move_)
+            .with_context(|| format!("Failed to replay move {move_index}: {move_:?}"))?;
+        Ok((
+            game.num_captured_by(Player::Black) - before.0,
+            game.num_captured_by(Player::White) - before.1,
+        ))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -42,6 +96,38 @@ pub enum Outcome {
     Unknown,
 }
 
+impl Outcome {
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            Outcome::WithWinner { winner, .. } => Some(*winner),
+            Outcome::Draw | Outcome::Void | Outcome::Unfinished | Outcome::Unknown => None,
+        }
+    }
+
+    /// Whether the game has a result, i.e. a winner or a draw
+    pub fn is_decided(&self) -> bool {
+        match self {
+            Outcome::WithWinner { .. } | Outcome::Draw => true,
+            Outcome::Void | Outcome::Unfinished | Outcome::Unknown => false,
+        }
+    }
+
+    /// Points the winner won by, or `None` if the game wasn't won on points
+    pub fn margin_points(&self) -> Option<f32> {
+        match self {
+            Outcome::WithWinner {
+                margin: OutcomeMargin::ByPoints { points_times_two },
+                ..
+            } => Some(*points_times_two as f32 / 2.0),
+            Outcome::WithWinner { .. }
+            | Outcome::Draw
+            | Outcome::Void
+            | Outcome::Unfinished
+            | Outcome::Unknown => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutcomeMargin {
     ByResign,
@@ -56,7 +142,12 @@ pub enum OutcomeMargin {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     Pass,
-    Place { x: u8, y: u8 },
+    Place {
+        x: u8,
+        y: u8,
+    },
+    /// SGF files don't have a resign move, they only record it in the `RE[B+R]`/`RE[W+R]` outcome
+    Resign,
 }
 
 fn parse_outcome(input: &str) -> Result<Outcome> {
@@ -69,7 +160,7 @@ fn parse_outcome(input: &str) -> Result<Outcome> {
     } else if let Some(margin) = input.strip_prefix("B+") {
         let margin = parse_margin(margin)?;
         Ok(Outcome::WithWinner {
-            winner: Player::White,
+            winner: Player::Black,
             margin,
         })
     } else if input == "Jigo" {
@@ -108,7 +199,152 @@ fn parse_margin(input: &str) -> Result<OutcomeMargin> {
 pub fn parse_sgf(sgf: &str) -> Result<SgfGame> {
     let games = sgf_parse::go::parse(sgf)?.into_iter();
     let game = single(games).context("Expected exactly one game in the SGF file")?;
-    let board_size = match game.get_property("SZ") {
+    let (outcome, mut initial_board) = parse_root(&game)?;
+    let time_settings = parse_time_settings(&game);
+    let mut current_player = Player::Black;
+
+    let mut moves = Vec::new();
+    let mut time_left = Vec::new();
+    let mut current_node = single(game.children())?;
+    loop {
+        let is_setup = is_setup_node(current_node);
+        if is_setup {
+            ensure!(
+                moves.is_empty(),
+                "Setup properties after the first move aren't supported"
+            );
+            apply_setup(current_node, &mut initial_board)?;
+        }
+        // A node can set up stones and then play a move
+        if !is_setup || has_move(current_node) {
+            moves.push(parse_move_node(current_node, current_player)?);
+            let time_left_property = match current_player {
+                Player::Black => "BL",
+                Player::White => "WL",
+            };
+            time_left.push(parse_time_left(current_node, time_left_property));
+            current_player = current_player.other_player();
+        }
+
+        match current_node.children().next() {
+            Some(next_node) => current_node = next_node,
+            None => break,
+        }
+    }
+    Ok(SgfGame {
+        outcome,
+        initial_board,
+        moves,
+        time_settings,
+        time_left,
+    })
+}
+
+/// A game record with all of its variations, see [parse_sgf_tree]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgfTree {
+    pub outcome: Outcome,
+    /// Position set up with the AB, AW and AE properties before the first move
+    pub initial_board: Board<BoardSize19x19>,
+    first_moves: Vec<SgfTreeNode>,
+}
+
+impl SgfTree {
+    /// The alternatives for the first move, the main line first
+    pub fn children(&self) -> &[SgfTreeNode] {
+        &self.first_moves
+    }
+
+    /// The moves of the main line, i.e. always following the first variation. These are the
+    /// moves [parse_sgf] returns.
+    pub fn main_line(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let mut children = self.children();
+        while let Some(node) = children.first() {
+            moves.push(node.game_move());
+            children = node.children();
+        }
+        moves
+    }
+}
+
+/// A move in an [SgfTree] with the variations that follow it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgfTreeNode {
+    game_move: Move,
+    children: Vec<SgfTreeNode>,
+}
+
+impl SgfTreeNode {
+    pub fn game_move(&self) -> Move {
+        self.game_move
+    }
+
+    /// The alternatives for the next move, the main line first
+    pub fn children(&self) -> &[SgfTreeNode] {
+        &self.children
+    }
+}
+
+/// Like [parse_sgf], but keeps all variations instead of only the main line, e.g. so a review
+/// tool can navigate them. Setup properties are only supported before and in the node of the
+/// first move, and the time properties aren't parsed.
+pub fn parse_sgf_tree(sgf: &str) -> Result<SgfTree> {
+    let games = sgf_parse::go::parse(sgf)?.into_iter();
+    let game = single(games).context("Expected exactly one game in the SGF file")?;
+    let (outcome, mut initial_board) = parse_root(&game)?;
+
+    let mut first_nodes: Vec<&SgfNode<Prop>> = game.children().collect();
+    while let [node] = first_nodes[..]
+        && is_setup_node(node)
+    {
+        apply_setup(node, &mut initial_board)?;
+        if has_move(node) {
+            // The setup applies before the move of the same node
+            return Ok(SgfTree {
+                outcome,
+                initial_board,
+                first_moves: vec![parse_tree_move(node, Player::Black)?],
+            });
+        }
+        first_nodes = node.children().collect();
+    }
+    let first_moves = first_nodes
+        .into_iter()
+        .map(|node| parse_tree_node(node, Player::Black))
+        .collect::<Result<_>>()?;
+    Ok(SgfTree {
+        outcome,
+        initial_board,
+        first_moves,
+    })
+}
+
+fn parse_tree_node(node: &SgfNode<Prop>, player: Player) -> Result<SgfTreeNode> {
+    ensure!(
+        !is_setup_node(node),
+        "Setup properties after the first move aren't supported"
+    );
+    parse_tree_move(node, player)
+}
+
+/// Like [parse_tree_node], but allows setup properties in `node` itself, for when they were
+/// already applied
+fn parse_tree_move(node: &SgfNode<Prop>, player: Player) -> Result<SgfTreeNode> {
+    let game_move = parse_move_node(node, player)?;
+    let children = node
+        .children()
+        .map(|child| parse_tree_node(child, player.other_player()))
+        .collect::<Result<_>>()?;
+    Ok(SgfTreeNode {
+        game_move,
+        children,
+    })
+}
+
+/// Outcome and board setup from the root node of a game
+fn parse_root(root: &SgfNode<Prop>) -> Result<(Outcome, Board<BoardSize19x19>)> {
+    let board_size = match root.get_property("SZ") {
         Some(Prop::SZ(size)) => size.clone(),
         None => (19, 19),
         _ => unreachable!(),
@@ -117,43 +353,291 @@ pub fn parse_sgf(sgf: &str) -> Result<SgfGame> {
         board_size == (19, 19),
         "Expected board size to be 19x19 but was {board_size:?}"
     );
-    let outcome = match game.get_property("RE") {
+    let outcome = match root.get_property("RE") {
         Some(Prop::RE(outcome)) => parse_outcome(&outcome.text)?,
         _ => unreachable!(),
     };
-    let mut current_player = Player::Black;
+    let mut initial_board = Board::new();
+    apply_setup(root, &mut initial_board)?;
+    Ok((outcome, initial_board))
+}
 
-    let mut moves = Vec::new();
-    let mut current_node = single(game.children())?;
-    loop {
-        if let Some(Prop::W(move_)) = current_node.get_property("W") {
-            ensure!(
-                current_node.get_property("B").is_none(),
-                "Node has both a W and B property"
-            );
-            ensure!(current_player == Player::White, "Expected White's turn");
-            current_player = Player::Black;
-            let mov = parse_move(&move_);
-            moves.push(mov);
-        } else if let Some(Prop::B(move_)) = current_node.get_property("B") {
+/// The move of a node with a B or W property, which must be by `player`
+fn parse_move_node(node: &SgfNode<Prop>, player: Player) -> Result<Move> {
+    let (move_, move_player) = match (node.get_property("B"), node.get_property("W")) {
+        (Some(Prop::B(move_)), None) => (move_, Player::Black),
+        (None, Some(Prop::W(move_))) => (move_, Player::White),
+        (Some(_), Some(_)) => bail!("Node has both a W and B property"),
+        _ => bail!("Node has neither a B nor a W property"),
+    };
+    ensure!(move_player == player, "Expected {player}'s turn");
+    Ok(parse_move(move_))
+}
+
+/// Invalid TM values are ignored like invalid BL and WL values, see [parse_time_left]
+fn parse_time_settings(root: &SgfNode<Prop>) -> Option<TimeSettings> {
+    let main_time = match root.get_property("TM") {
+        Some(Prop::TM(seconds)) => parse_duration(*seconds).ok(),
+        _ => None,
+    };
+    let overtime = match root.get_property("OT") {
+        Some(Prop::OT(overtime)) => Some(overtime.text.clone()),
+        _ => None,
+    };
+    if main_time.is_none() && overtime.is_none() {
+        return None;
+    }
+    Some(TimeSettings {
+        main_time,
+        overtime,
+    })
+}
+
+/// Time left from the BL or WL property of a move node
+/// Negative values count as no time left, and invalid ones are ignored rather than failing the game
+fn parse_time_left(node: &SgfNode<Prop>, identifier: &str) -> Option<Duration> {
+    match node.get_property(identifier) {
+        Some(Prop::BL(seconds) | Prop::WL(seconds)) => parse_duration(seconds.max(0.0)).ok(),
+        _ => None,
+    }
+}
+
+fn parse_duration(seconds: f64) -> Result<Duration> {
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| anyhow!("Expected a non-negative number of seconds but got {seconds}"))
+}
+
+fn has_move(node: &SgfNode<Prop>) -> bool {
+    node.get_property("B").is_some() || node.get_property("W").is_some()
+}
+
+fn is_setup_node(node: &SgfNode<Prop>) -> bool {
+    ["AB", "AW", "AE"]
+        .into_iter()
+        .any(|identifier| node.get_property(identifier).is_some())
+}
+
+/// Applies the setup properties of `node` to `board`: AE clears points, AB and AW add stones
+fn apply_setup(node: &SgfNode<Prop>, board: &mut Board<BoardSize19x19>) -> Result<()> {
+    let mut set_points = |points: &HashSet<Point>, value| -> Result<()> {
+        for point in points {
+            let (x, y) = (usize::from(point.x), usize::from(point.y));
             ensure!(
-                current_node.get_property("W").is_none(),
-                "Node has both a W and B property"
+                x < 19 && y < 19,
+                "Setup point {point:?} is outside of the board"
             );
-            ensure!(current_player == Player::Black, "Expected White's turn");
-            current_player = Player::White;
-            let mov = parse_move(&move_);
-            moves.push(mov);
-        } else {
-            bail!("Node has neither a B nor a W property");
+            board.set(Pos::from_xy(x, y), value);
+        }
+        Ok(())
+    };
+    if let Some(Prop::AE(points)) = node.get_property("AE") {
+        set_points(points, None)?;
+    }
+    if let Some(Prop::AB(points)) = node.get_property("AB") {
+        set_points(points, Some(Player::Black))?;
+    }
+    if let Some(Prop::AW(points)) = node.get_property("AW") {
+        set_points(points, Some(Player::White))?;
+    }
+    Ok(())
+}
+
+pub fn parse_sgf_file(path: impl AsRef<Path>) -> Result<SgfGame> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    parse_sgf_reader(file).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Key facts about a game, see [analyze_sgf_dir]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameSummary {
+    /// Number of moves in the main line, including passes
+    pub num_moves: usize,
+    pub outcome: Outcome,
+    pub winner: Option<Player>,
+}
+
+impl GameSummary {
+    fn new(game: &SgfGame) -> Self {
+        Self {
+            num_moves: game.moves.len(),
+            outcome: game.outcome,
+            winner: game.outcome.winner(),
         }
+    }
+}
 
-        match current_node.children().next() {
-            Some(next_node) => current_node = next_node,
-            None => break,
+/// Parses all `.sgf` files directly in the directory `path`, sorted by file name. Files that can't be
+/// read or parsed get their error instead of a summary, so one broken file doesn't abort the whole
+/// batch. Only fails if the directory itself can't be listed.
+pub fn analyze_sgf_dir(path: impl AsRef<Path>) -> Result<Vec<(PathBuf, Result<GameSummary>)>> {
+    let path = path.as_ref();
+    let mut files = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to list {}", path.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<PathBuf>>>()
+        .with_context(|| format!("Failed to list {}", path.display()))?;
+    files.retain(|file| {
+        file.is_file()
+            && file
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("sgf"))
+    });
+    files.sort();
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            let summary = parse_sgf_file(&file).map(|game| GameSummary::new(&game));
+            (file, summary)
+        })
+        .collect())
+}
+
+pub fn parse_sgf_reader(mut reader: impl Read) -> Result<SgfGame> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse_sgf(&decode_sgf(&bytes)?)
+}
+
+/// Parses a collection of games one game at a time, so that huge SGF databases don't need
+/// to be held in memory at once. Iteration stops after the first error reading the input.
+pub fn parse_sgf_stream<R: Read>(reader: R) -> impl Iterator<Item = Result<SgfGame>> {
+    SgfStream {
+        bytes: BufReader::new(reader).bytes(),
+        done: false,
+    }
+    .map(|game| game.and_then(|bytes| parse_sgf(&decode_sgf(&bytes)?)))
+}
+
+/// Splits the input into the bytes of each top level game tree, i.e. at parentheses
+/// that aren't inside a property value.
+struct SgfStream<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for SgfStream<R> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        if self.done {
+            return None;
+        }
+        let mut game = Vec::new();
+        let mut depth = 0usize;
+        let mut in_value = false;
+        let mut escaped = false;
+        loop {
+            let byte = match self.bytes.next() {
+                Some(Ok(byte)) => byte,
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Some(Err(err.into()));
+                }
+                None => {
+                    self.done = true;
+                    return if game.is_empty() {
+                        None
+                    } else {
+                        Some(Err(anyhow!("Unexpected end of SGF input inside a game")))
+                    };
+                }
+            };
+            if depth == 0 {
+                // Skip whitespace and anything else between games
+                if byte == b'(' {
+                    depth = 1;
+                    game.push(byte);
+                }
+                continue;
+            }
+            game.push(byte);
+            if in_value {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b']' => in_value = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'[' => in_value = true,
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(Ok(game));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// SGF files declare their encoding in the CA property. Without it, the spec says Latin-1,
+/// but most files in the wild are UTF-8, so we try that first.
+fn decode_sgf(bytes: &[u8]) -> Result<String> {
+    match charset(bytes).as_deref() {
+        None => Ok(String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| decode_latin1(bytes))),
+        Some("utf-8" | "utf8") => {
+            String::from_utf8(bytes.to_vec()).context("SGF declares UTF-8 but isn't valid UTF-8")
+        }
+        Some("iso-8859-1" | "iso8859-1" | "latin-1" | "latin1") => Ok(decode_latin1(bytes)),
+        Some(charset) => bail!("Unsupported SGF charset: {charset}"),
+    }
+}
+
+/// Finds the value of the CA property, lowercased. This looks at the raw bytes since
+/// we need it before we can decode the file. Only the properties of the root node are
+/// looked at, so text in comments or later nodes can't change the decoding.
+fn charset(bytes: &[u8]) -> Option<String> {
+    let root = bytes.iter().position(|&byte| byte == b';')? + 1;
+    let mut bytes = bytes[root..].iter().copied();
+    let mut identifier = Vec::new();
+    let mut after_value = false;
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'[' => {
+                let mut value = Vec::new();
+                let mut escaped = false;
+                for byte in bytes.by_ref() {
+                    match byte {
+                        _ if escaped => {
+                            escaped = false;
+                            value.push(byte);
+                        }
+                        b'\\' => escaped = true,
+                        b']' => break,
+                        _ => value.push(byte),
+                    }
+                }
+                if identifier == b"CA" {
+                    let charset = std::str::from_utf8(&value).ok()?;
+                    return Some(charset.trim().to_ascii_lowercase());
+                }
+                after_value = true;
+            }
+            b'A'..=b'Z' => {
+                // Properties can have several values, a new identifier only starts after them
+                if after_value {
+                    identifier.clear();
+                    after_value = false;
+                }
+                identifier.push(byte);
+            }
+            // The root node ended
+            b';' | b'(' | b')' => return None,
+            _ => {}
         }
     }
-    Ok(SgfGame { outcome, moves })
+    None
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    // Latin-1 code points map one-to-one onto the first 256 unicode code points
+    bytes.iter().map(|&byte| char::from(byte)).collect()
 }
 
 fn parse_move(input: &sgf_parse::go::Move) -> Move {
@@ -181,6 +665,495 @@ mod tests {
 
     const GAME_SGF: &str = include_str!("../tests/sixteen_soldiers.sgf");
 
+    #[test]
+    fn outcome_with_winner_by_points() {
+        let outcome = Outcome::WithWinner {
+            winner: Player::Black,
+            margin: OutcomeMargin::ByPoints {
+                points_times_two: 21,
+            },
+        };
+        assert_eq!(Some(Player::Black), outcome.winner());
+        assert!(outcome.is_decided());
+        assert_eq!(Some(10.5), outcome.margin_points());
+    }
+
+    #[test]
+    fn outcome_with_winner_by_resign_time_or_forfeit() {
+        for margin in [
+            OutcomeMargin::ByResign,
+            OutcomeMargin::ByTime,
+            OutcomeMargin::ByForfeit,
+        ] {
+            let outcome = Outcome::WithWinner {
+                winner: Player::White,
+                margin,
+            };
+            assert_eq!(Some(Player::White), outcome.winner());
+            assert!(outcome.is_decided());
+            assert_eq!(None, outcome.margin_points());
+        }
+    }
+
+    #[test]
+    fn winner_of_parsed_outcome() {
+        let winner = |result: &str| {
+            parse_sgf(&format!("(;GM[1]FF[4]SZ[19]RE[{result}];B[aa])"))
+                .unwrap()
+                .outcome
+                .winner()
+        };
+        assert_eq!(Some(Player::Black), winner("B+R"));
+        assert_eq!(Some(Player::Black), winner("B+2.5"));
+        assert_eq!(Some(Player::White), winner("W+T"));
+        assert_eq!(None, winner("Jigo"));
+        assert_eq!(None, winner("Void"));
+    }
+
+    #[test]
+    fn outcome_draw() {
+        assert_eq!(None, Outcome::Draw.winner());
+        assert!(Outcome::Draw.is_decided());
+        assert_eq!(None, Outcome::Draw.margin_points());
+    }
+
+    #[test]
+    fn outcome_without_result() {
+        for outcome in [Outcome::Void, Outcome::Unfinished, Outcome::Unknown] {
+            assert_eq!(None, outcome.winner());
+            assert!(!outcome.is_decided());
+            assert_eq!(None, outcome.margin_points());
+        }
+    }
+
+    #[test]
+    fn replaying_move_outside_of_board_fails() {
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[dd];W[zz];B[ee])").unwrap();
+        assert!(sgf_game.game_position_after_num_moves(1).is_ok());
+        let error = sgf_game.game_position_after_num_moves(2).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("Move 25/25 is outside of the board"),
+            "{error:#}"
+        );
+        assert!(sgf_game.captures_at(1).is_err());
+    }
+
+    #[test]
+    fn small_board_sgf_is_rejected() {
+        // Only 19x19 games can be parsed so far, so the board size is rejected before W[kk] is read.
+        // A move outside of a 9x9 board can only be tested once other board sizes are supported.
+        let error = parse_sgf("(;GM[1]FF[4]SZ[9]RE[B+R];B[dd];W[kk])").unwrap_err();
+        assert!(
+            format!("{error:#}").contains("Expected board size to be 19x19"),
+            "{error:#}"
+        );
+    }
+
+    #[test]
+    fn test_parse_sgf_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/sixteen_soldiers.sgf");
+        assert_eq!(parse_sgf(GAME_SGF).unwrap(), parse_sgf_file(path).unwrap());
+    }
+
+    #[test]
+    fn test_parse_sgf_file_not_found() {
+        assert!(parse_sgf_file("does/not/exist.sgf").is_err());
+    }
+
+    #[test]
+    fn test_analyze_sgf_dir() {
+        let dir =
+            std::env::temp_dir().join(format!("go-game-analyze-sgf-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("a.sgf"),
+            "(;GM[1]FF[4]SZ[19]RE[W+3.5];B[dd];W[pp];B[];W[])",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("b.sgf"),
+            "(;GM[1]FF[4]SZ[19]RE[B+10.5];B[dd];W[pp])",
+        )
+        .unwrap();
+        std::fs::write(dir.join("c.sgf"), "(;GM[1]FF[4]SZ[19]RE[Jigo];B[dd])").unwrap();
+        std::fs::write(dir.join("d.sgf"), "(;GM[1]FF[4]SZ[19]RE[W+R];B[dd]").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not an sgf").unwrap();
+
+        let summaries = analyze_sgf_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let summaries = summaries.unwrap();
+
+        assert_eq!(4, summaries.len());
+        assert_eq!(dir.join("a.sgf"), summaries[0].0);
+        assert_eq!(
+            GameSummary {
+                num_moves: 4,
+                outcome: Outcome::WithWinner {
+                    winner: Player::White,
+                    margin: OutcomeMargin::ByPoints {
+                        points_times_two: 7
+                    },
+                },
+                winner: Some(Player::White),
+            },
+            *summaries[0].1.as_ref().unwrap()
+        );
+        assert_eq!(dir.join("b.sgf"), summaries[1].0);
+        assert_eq!(
+            GameSummary {
+                num_moves: 2,
+                outcome: Outcome::WithWinner {
+                    winner: Player::Black,
+                    margin: OutcomeMargin::ByPoints {
+                        points_times_two: 21
+                    },
+                },
+                winner: Some(Player::Black),
+            },
+            *summaries[1].1.as_ref().unwrap()
+        );
+        assert_eq!(dir.join("c.sgf"), summaries[2].0);
+        assert_eq!(
+            GameSummary {
+                num_moves: 1,
+                outcome: Outcome::Draw,
+                winner: None,
+            },
+            *summaries[2].1.as_ref().unwrap()
+        );
+        assert_eq!(dir.join("d.sgf"), summaries[3].0);
+        assert!(summaries[3].1.is_err());
+    }
+
+    #[test]
+    fn test_analyze_sgf_dir_not_found() {
+        assert!(analyze_sgf_dir("does/not/exist").is_err());
+    }
+
+    #[test]
+    fn test_parse_sgf_reader() {
+        assert_eq!(
+            parse_sgf(GAME_SGF).unwrap(),
+            parse_sgf_reader(GAME_SGF.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_sgf_stream() {
+        let collection = concat!(
+            "(;GM[1]FF[4]SZ[19]RE[B+R];B[ba];W[aa])\n",
+            "(;GM[1]FF[4]SZ[19]RE[W+R]C[a comment with ( and \\] inside];B[cc])\n\n",
+            "(;GM[1]FF[4]SZ[19]RE[B+R];B[dd];W[];B[ee])",
+        );
+        let games = parse_sgf_stream(collection.as_bytes())
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(3, games.len());
+        assert_eq!(
+            vec![Move::Place { x: 1, y: 0 }, Move::Place { x: 0, y: 0 }],
+            games[0].moves
+        );
+        assert_eq!(vec![Move::Place { x: 2, y: 2 }], games[1].moves);
+        assert_eq!(
+            vec![
+                Move::Place { x: 3, y: 3 },
+                Move::Pass,
+                Move::Place { x: 4, y: 4 }
+            ],
+            games[2].moves
+        );
+        assert_eq!(
+            parse_sgf(GAME_SGF).unwrap(),
+            parse_sgf_stream(GAME_SGF.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_sgf_stream_truncated() {
+        let mut games = parse_sgf_stream("(;GM[1]SZ[19]RE[B+R];B[ba]) (;GM[1];B[".as_bytes());
+        assert!(games.next().unwrap().is_ok());
+        assert!(games.next().unwrap().is_err());
+        assert!(games.next().is_none());
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        assert_eq!(
+            "(;CA[UTF-8]C[caf\u{e9}])",
+            decode_sgf("(;CA[UTF-8]C[caf\u{e9}])".as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        assert_eq!(
+            "(;CA[ISO-8859-1]C[caf\u{e9}])",
+            decode_sgf(b"(;CA[ISO-8859-1]C[caf\xe9])").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_without_charset() {
+        assert_eq!(
+            "(;C[caf\u{e9}])",
+            decode_sgf("(;C[caf\u{e9}])".as_bytes()).unwrap()
+        );
+        assert_eq!("(;C[caf\u{e9}])", decode_sgf(b"(;C[caf\xe9])").unwrap());
+    }
+
+    #[test]
+    fn test_decode_ignores_charset_outside_of_root_properties() {
+        // In a comment
+        assert_eq!(
+            "(;C[use CA[UTF-8\\]]C[caf\u{e9}])",
+            decode_sgf(b"(;C[use CA[UTF-8\\]]C[caf\xe9])").unwrap()
+        );
+        // In a later node
+        assert_eq!(
+            "(;C[caf\u{e9}];B[aa]CA[UTF-8])",
+            decode_sgf(b"(;C[caf\xe9];B[aa]CA[UTF-8])").unwrap()
+        );
+        // After a property with several values
+        assert!(decode_sgf(b"(;AB[aa][bb]CA[UTF-8]C[caf\xe9])").is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        assert!(decode_sgf(b"(;CA[UTF-8]C[caf\xe9])").is_err());
+    }
+
+    #[test]
+    fn test_decode_unsupported_charset() {
+        assert!(decode_sgf(b"(;CA[Shift_JIS]C[])").is_err());
+    }
+
+    #[test]
+    fn test_parse_sgf_tree() {
+        // Black's second move has two variations, and the first one has two answers by White
+        let sgf = "(;GM[1]FF[4]SZ[19]RE[W+R];B[aa];W[bb](;B[cc](;W[dd])(;W[ee]))(;B[ff];W[gg]))";
+        let tree = parse_sgf_tree(sgf).unwrap();
+        let place = |x, y| Move::Place { x, y };
+
+        assert_eq!(1, tree.children().len());
+        let first = &tree.children()[0];
+        assert_eq!(place(0, 0), first.game_move());
+        assert_eq!(1, first.children().len());
+        let second = &first.children()[0];
+        assert_eq!(place(1, 1), second.game_move());
+        let variations: Vec<Move> = second.children().iter().map(|n| n.game_move()).collect();
+        assert_eq!(vec![place(2, 2), place(5, 5)], variations);
+        let answers: Vec<Move> = second.children()[0]
+            .children()
+            .iter()
+            .map(|n| n.game_move())
+            .collect();
+        assert_eq!(vec![place(3, 3), place(4, 4)], answers);
+        assert_eq!(1, second.children()[1].children().len());
+
+        let linear = parse_sgf(sgf).unwrap();
+        assert_eq!(linear.moves, tree.main_line());
+        assert_eq!(linear.outcome, tree.outcome);
+        assert_eq!(linear.initial_board, tree.initial_board);
+    }
+
+    #[test]
+    fn test_parse_sgf_tree_with_variations_of_the_first_move() {
+        let tree = parse_sgf_tree("(;GM[1]FF[4]SZ[19]RE[W+R]AB[dd](;B[aa])(;B[bb]))").unwrap();
+        assert_eq!(2, tree.children().len());
+        assert_eq!(vec![Move::Place { x: 0, y: 0 }], tree.main_line());
+        assert_eq!(Some(Player::Black), tree.initial_board[Pos::from_xy(3, 3)]);
+    }
+
+    #[test]
+    fn test_parse_sgf_tree_of_fixture() {
+        let tree = parse_sgf_tree(GAME_SGF).unwrap();
+        assert_eq!(parse_sgf(GAME_SGF).unwrap().moves, tree.main_line());
+    }
+
+    #[test]
+    fn test_parse_sgf_tree_with_wrong_player_in_variation() {
+        let error = parse_sgf_tree("(;GM[1]FF[4]SZ[19]RE[W+R];B[aa](;W[bb])(;B[cc]))").unwrap_err();
+        assert_eq!("Expected White's turn", error.to_string());
+    }
+
+    #[test]
+    fn test_parse_time_settings() {
+        let sgf_game = parse_sgf(
+            "(;GM[1]FF[4]SZ[19]RE[B+T]TM[3600]OT[5x30 byo-yomi];B[aa]BL[3590.5];W[bb]WL[3512];B[cc])",
+        )
+        .unwrap();
+        assert_eq!(
+            Some(TimeSettings {
+                main_time: Some(Duration::from_secs(3600)),
+                overtime: Some("5x30 byo-yomi".to_string()),
+            }),
+            sgf_game.time_settings
+        );
+        assert_eq!(
+            vec![
+                Some(Duration::from_millis(3_590_500)),
+                Some(Duration::from_secs(3512)),
+                None
+            ],
+            sgf_game.time_left
+        );
+    }
+
+    #[test]
+    fn test_parse_without_time_settings() {
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[aa];W[bb])").unwrap();
+        assert_eq!(None, sgf_game.time_settings);
+        assert_eq!(vec![None, None], sgf_game.time_left);
+
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R]OT[3x60 byo-yomi];B[aa])").unwrap();
+        assert_eq!(
+            Some(TimeSettings {
+                main_time: None,
+                overtime: Some("3x60 byo-yomi".to_string()),
+            }),
+            sgf_game.time_settings
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_main_time() {
+        let sgf_game =
+            parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R]TM[-60]OT[3x60 byo-yomi];B[aa]BL[12])").unwrap();
+        assert_eq!(
+            Some(TimeSettings {
+                main_time: None,
+                overtime: Some("3x60 byo-yomi".to_string()),
+            }),
+            sgf_game.time_settings
+        );
+        assert_eq!(vec![Some(Duration::from_secs(12))], sgf_game.time_left);
+
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R]TM[inf];B[aa])").unwrap();
+        assert_eq!(None, sgf_game.time_settings);
+    }
+
+    #[test]
+    fn test_parse_negative_time_left() {
+        let sgf_game =
+            parse_sgf("(;GM[1]FF[4]SZ[19]RE[W+T];B[aa]BL[12];W[bb];B[cc]BL[-5])").unwrap();
+        assert_eq!(
+            vec![Some(Duration::from_secs(12)), None, Some(Duration::ZERO)],
+            sgf_game.time_left
+        );
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let outcome = |result: &str| {
+            parse_sgf(&format!("(;GM[1]FF[4]SZ[19]RE[{result}];B[aa])"))
+                .unwrap()
+                .outcome
+        };
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::Black,
+                margin: OutcomeMargin::ByResign,
+            },
+            outcome("B+R")
+        );
+        assert_eq!(
+            Outcome::WithWinner {
+                winner: Player::White,
+                margin: OutcomeMargin::ByPoints {
+                    points_times_two: 1
+                },
+            },
+            outcome("W+0.5")
+        );
+        assert_eq!(Outcome::Draw, outcome("Jigo"));
+    }
+
+    #[test]
+    fn test_replay_with_mid_game_pass() {
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[ba];W[aa];B[];W[cc];B[ab])").unwrap();
+        assert_eq!(Move::Pass, sgf_game.moves[2]);
+        let game = sgf_game.game_position_after_num_moves(5).unwrap();
+        let mut expected_board = Board::<BoardSize19x19>::new();
+        expected_board.set(Pos::from_xy(1, 0), Some(Player::Black));
+        expected_board.set(Pos::from_xy(0, 1), Some(Player::Black));
+        expected_board.set(Pos::from_xy(2, 2), Some(Player::White));
+        assert_eq!(&expected_board, game.board());
+        assert!(!game.is_game_over());
+        assert_eq!(
+            (NumStones::ONE, NumStones::ZERO),
+            sgf_game.captures_at(4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replay_rejects_moves_after_game_ended() {
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[ba];W[];B[];W[cc])").unwrap();
+        assert!(
+            sgf_game
+                .game_position_after_num_moves(3)
+                .unwrap()
+                .is_game_over()
+        );
+        assert!(sgf_game.game_position_after_num_moves(4).is_err());
+        assert!(sgf_game.captures_at(3).is_err());
+    }
+
+    #[test]
+    fn test_setup_position() {
+        let sgf_game = parse_sgf(
+            "(;GM[1]FF[4]SZ[19]RE[B+R]AB[aa][bb]AW[cc];AE[bb]AW[dd];AB[bb]AE[aa];B[ee];W[ff])",
+        )
+        .unwrap();
+        let mut expected_board = Board::<BoardSize19x19>::new();
+        expected_board.set(Pos::from_xy(1, 1), Some(Player::Black));
+        expected_board.set(Pos::from_xy(2, 2), Some(Player::White));
+        expected_board.set(Pos::from_xy(3, 3), Some(Player::White));
+        assert_eq!(expected_board, sgf_game.initial_board);
+        assert_eq!(
+            vec![Move::Place { x: 4, y: 4 }, Move::Place { x: 5, y: 5 }],
+            sgf_game.moves
+        );
+
+        let game = sgf_game.game_position_after_num_moves(2).unwrap();
+        expected_board.set(Pos::from_xy(4, 4), Some(Player::Black));
+        expected_board.set(Pos::from_xy(5, 5), Some(Player::White));
+        assert_eq!(&expected_board, game.board());
+    }
+
+    #[test]
+    fn test_setup_after_moves_is_rejected() {
+        assert!(parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[aa];AE[aa];W[bb])").is_err());
+        assert!(parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[aa];AB[cc]W[bb])").is_err());
+    }
+
+    #[test]
+    fn test_setup_and_move_in_the_same_node() {
+        let sgf = "(;GM[1]FF[4]SZ[19]RE[B+R];AB[aa]AW[dd]B[bb];W[cc])";
+        let mut expected_board = Board::<BoardSize19x19>::new();
+        expected_board.set(Pos::from_xy(0, 0), Some(Player::Black));
+        expected_board.set(Pos::from_xy(3, 3), Some(Player::White));
+        let expected_moves = vec![Move::Place { x: 1, y: 1 }, Move::Place { x: 2, y: 2 }];
+
+        let sgf_game = parse_sgf(sgf).unwrap();
+        assert_eq!(expected_board, sgf_game.initial_board);
+        assert_eq!(expected_moves, sgf_game.moves);
+        assert_eq!(vec![None, None], sgf_game.time_left);
+
+        let tree = parse_sgf_tree(sgf).unwrap();
+        assert_eq!(expected_board, tree.initial_board);
+        assert_eq!(expected_moves, tree.main_line());
+    }
+
+    #[test]
+    fn test_replay_too_many_moves() {
+        let sgf_game = parse_sgf("(;GM[1]FF[4]SZ[19]RE[B+R];B[ba];W[aa])").unwrap();
+        assert!(sgf_game.game_position_after_num_moves(2).is_ok());
+        assert!(sgf_game.game_position_after_num_moves(3).is_err());
+    }
+
     #[test]
     fn test_parse_sgf() {
         let parsed = parse_sgf(GAME_SGF).unwrap();
@@ -188,6 +1161,8 @@ mod tests {
         assert_eq!(
             parsed,
             SgfGame {
+                initial_board: Board::new(),
+                time_settings: None,
                 outcome: Outcome::WithWinner {
                     winner: Player::White,
                     margin: OutcomeMargin::ByResign,
@@ -349,7 +1324,8 @@ mod tests {
                     Move::Place { x: 10, y: 5 },
                     Move::Place { x: 5, y: 3 },
                     Move::Place { x: 15, y: 15 }
-                ]
+                ],
+                time_left: vec![None; parsed.moves.len()],
             }
         )
     }
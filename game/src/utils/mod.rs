@@ -3,3 +3,6 @@ pub use ints::IntType;
 
 mod small_set;
 pub use small_set::SmallSet;
+
+mod rng;
+pub use rng::{Rng, mix};
@@ -125,9 +125,11 @@ where
     where
         T: IntoIterator<Item = A::Item>,
     {
-        SmallSet {
-            elements: SmallVec::from_iter(iter),
+        let mut set = SmallSet::new();
+        for elem in iter {
+            set.insert(elem);
         }
+        set
     }
 }
 
@@ -196,4 +198,10 @@ mod test {
         let s: SmallSet<[usize; 4]> = vec![1, 2, 3, 4].into_iter().collect();
         assert!(s.len() == 4);
     }
+
+    #[test]
+    fn test_fromiter_with_duplicates() {
+        let s: SmallSet<[usize; 4]> = vec![1, 2, 1, 3, 2].into_iter().collect();
+        assert!(s.len() == 3);
+    }
 }
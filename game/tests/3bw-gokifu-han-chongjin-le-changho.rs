@@ -2,9 +2,11 @@
 
 // From http://gokifu.com/ , 2025-04-06, Han Chongjin against Le Changho, W+1.5
 
+mod common;
+
+use common::assert_board_eq;
 use common_macros::hash_map;
 use go_game::{Board, parse_sgf};
-use pretty_assertions::assert_eq;
 
 const GAME_SGF: &str = include_str!("3bw-gokifu-han-chongjin-le-changho.sgf");
 
@@ -521,12 +523,13 @@ fn game_3bw_gokifu() {
     };
 
     for (move_index, expected_board) in expected_boards.iter() {
-        assert_eq!(
+        assert_board_eq!(
             expected_board,
             sgf_game
                 .game_position_after_num_moves(*move_index)
                 .unwrap()
-                .board()
+                .board(),
+            "after {move_index} moves"
         );
     }
 }
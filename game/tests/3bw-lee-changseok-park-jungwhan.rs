@@ -3,8 +3,11 @@
 // From http://gokifu.com/ , 2025-04-03, Lee Changseok vs Park Jungwhan, B+10.5
 // This is a game with a group capture of more than one stone
 
+mod common;
+
+use common::assert_board_eq;
 use common_macros::hash_map;
-use go_game::{Board, parse_sgf};
+use go_game::{Board, NumStones, Player, parse_sgf};
 use pretty_assertions::assert_eq;
 
 const GAME_SGF: &str = include_str!("3bw-lee-changseok-park-jungwhan.sgf");
@@ -60,12 +63,36 @@ fn game_3bw_gokifu() {
     };
 
     for (move_index, expected_board) in expected_boards.iter() {
-        assert_eq!(
+        assert_board_eq!(
             expected_board,
             sgf_game
                 .game_position_after_num_moves(*move_index)
                 .unwrap()
-                .board()
+                .board(),
+            "after {move_index} moves"
         );
     }
 }
+
+#[test]
+fn game_3bw_gokifu_captures() {
+    let sgf_game = parse_sgf(GAME_SGF).unwrap();
+
+    // Black's move 255 captures the six white stones in the lower right corner
+    assert_eq!(
+        (NumStones::from_usize(6), NumStones::ZERO),
+        sgf_game.captures_at(254).unwrap()
+    );
+    assert_eq!(
+        (NumStones::ZERO, NumStones::ZERO),
+        sgf_game.captures_at(0).unwrap()
+    );
+    assert!(sgf_game.captures_at(sgf_game.moves.len()).is_err());
+}
+
+#[test]
+fn game_3bw_gokifu_winner() {
+    let sgf_game = parse_sgf(GAME_SGF).unwrap();
+    assert_eq!(Some(Player::Black), sgf_game.outcome.winner());
+    assert_eq!(Some(10.5), sgf_game.outcome.margin_points());
+}
@@ -0,0 +1,49 @@
+use go_game::{Board, BoardSize, Player, Pos};
+
+/// Like `assert_eq!` for boards, but on failure it only lists the cells that differ
+/// instead of printing both boards.
+macro_rules! assert_board_eq {
+    ($expected:expr, $actual:expr $(,)?) => {
+        $crate::common::assert_board_eq_impl($expected, $actual, None)
+    };
+    ($expected:expr, $actual:expr, $($arg:tt)+) => {
+        $crate::common::assert_board_eq_impl($expected, $actual, Some(format!($($arg)+)))
+    };
+}
+pub(crate) use assert_board_eq;
+
+#[track_caller]
+pub fn assert_board_eq_impl<BS: BoardSize>(
+    expected: &Board<BS>,
+    actual: &Board<BS>,
+    message: Option<String>,
+) where
+    [(); bitvec::mem::elts::<usize>(2 * <BS as BoardSize>::SIZE * <BS as BoardSize>::SIZE)]:,
+{
+    let differences: Vec<String> = Pos::<BS>::all_positions()
+        .filter(|pos| expected[*pos] != actual[*pos])
+        .map(|pos| {
+            format!(
+                "  {pos}: expected {}, actual {}",
+                cell_name(expected[pos]),
+                cell_name(actual[pos])
+            )
+        })
+        .collect();
+    if !differences.is_empty() {
+        let context = message.map(|m| format!(": {m}")).unwrap_or_default();
+        panic!(
+            "Boards differ in {} cells{context}\n{}",
+            differences.len(),
+            differences.join("\n")
+        );
+    }
+}
+
+fn cell_name(cell: Option<Player>) -> &'static str {
+    match cell {
+        Some(Player::Black) => "black",
+        Some(Player::White) => "white",
+        None => "empty",
+    }
+}
@@ -2,6 +2,9 @@
 
 // From https://senseis.xmp.net/?SixteenSoldiers
 
+mod common;
+
+use common::assert_board_eq;
 use common_macros::hash_map;
 use go_game::{Board, parse_sgf};
 
@@ -141,12 +144,13 @@ fn sixteen_soldiers() {
     };
 
     for (move_index, expected_board) in expected_boards.iter() {
-        assert_eq!(
+        assert_board_eq!(
             expected_board,
             sgf_game
                 .game_position_after_num_moves(*move_index)
                 .unwrap()
-                .board()
+                .board(),
+            "after {move_index} moves"
         );
     }
 }
@@ -1,24 +1,39 @@
-use actually_beep::beep_with_hz_and_millis;
 use crossterm::event::{Event, KeyCode};
-use go_game::BoardSize9x9;
+use go_game::{BoardSize9x9, Notation, Origin, Player, Pos};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::widgets::Block;
 use tui_logger::TuiLoggerWidget;
 
-use crate::game_widget::GameWidget;
+use crate::feedback::{Beep, Feedback};
+use crate::game_widget::{GameWidget, outcome_text};
 
 pub struct App {
     // TODO Offer larger board sizes
     game: GameWidget<BoardSize9x9>,
 
+    /// Color played by the engine, or `None` if both colors are played by humans
+    engine_player: Option<Player>,
+
+    feedback: Box<dyn Feedback>,
+    /// Toggled with M, suppresses all [Feedback]
+    muted: bool,
+
     should_exit: bool,
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_feedback(Box::new(Beep))
+    }
+
+    /// Like [Self::new], but tells the user about errors with `feedback` instead of a beep
+    pub fn with_feedback(feedback: Box<dyn Feedback>) -> Self {
         Self {
             game: GameWidget::new(),
+            engine_player: None,
+            feedback,
+            muted: false,
             should_exit: false,
         }
     }
@@ -47,10 +62,53 @@ impl App {
                     KeyCode::Down => {
                         self.game.move_down();
                     }
+                    KeyCode::Home => {
+                        self.game.move_to_left_edge();
+                    }
+                    KeyCode::End => {
+                        self.game.move_to_right_edge();
+                    }
+                    KeyCode::PageUp => {
+                        self.game.move_to_top_edge();
+                    }
+                    KeyCode::PageDown => {
+                        self.game.move_to_bottom_edge();
+                    }
+                    KeyCode::Char('c') => {
+                        self.game.move_to_center();
+                    }
+                    KeyCode::Char('m') => {
+                        self.muted = !self.muted;
+                        log::info!("Sound {}", if self.muted { "muted" } else { "unmuted" });
+                    }
+                    KeyCode::Char('d') if self.game.is_scoring() => {
+                        self.game.toggle_dead_group();
+                    }
+                    KeyCode::Enter if self.game.is_scoring() => {
+                        let outcome = self.game.finish_scoring();
+                        log::info!("Game finished: {}", outcome_text(outcome));
+                    }
+                    // Once both players passed, there is no more placing or passing
+                    _ if self.game.is_scoring() || self.game.final_outcome().is_some() => (),
+                    KeyCode::Char('a') => {
+                        match self.engine_player {
+                            Some(player) => {
+                                self.engine_player = None;
+                                log::info!("Computer opponent stopped playing {player}");
+                            }
+                            None => {
+                                // The engine takes the color that isn't to move, so it's still the human's turn
+                                let player = self.game.current_player().other_player();
+                                self.engine_player = Some(player);
+                                log::info!("Computer opponent plays {player}");
+                            }
+                        }
+                    }
                     KeyCode::Char('p') => {
                         let player = self.game.current_player();
                         self.game.pass_turn();
-                        log::info!("{player}: pass turn");
+                        self.log_pass(player);
+                        self.play_engine_turn();
                     }
                     KeyCode::Enter | KeyCode::Char(' ') => {
                         let player = self.game.current_player();
@@ -58,19 +116,18 @@ impl App {
                         match self.game.place_stone() {
                             Ok(()) => {
                                 log::info!(
-                                    // TODO Should the origin be bottom left or top left of the board?
                                     "{player}: placed stone at {}",
-                                    current_pos,
+                                    coordinates(current_pos)
                                 );
+                                self.play_engine_turn();
                             }
                             Err(e) => {
                                 log::error!(
-                                    // TODO Same here, which origin?
                                     "{player}: Failed to place stone at {}: {:?}",
-                                    current_pos,
+                                    coordinates(current_pos),
                                     e
                                 );
-                                beep_with_hz_and_millis(200, 75).unwrap();
+                                self.error_feedback();
                             }
                         }
                     }
@@ -81,6 +138,35 @@ impl App {
         }
     }
 
+    /// If the engine is to move, let it reply to the human's move
+    fn play_engine_turn(&mut self) {
+        if self.game.is_scoring() || self.game.final_outcome().is_some() {
+            return;
+        }
+        let player = self.game.current_player();
+        if self.engine_player != Some(player) {
+            return;
+        }
+        log::info!("{player}: thinking...");
+        match self.game.play_engine_move() {
+            Some(pos) => log::info!("{player}: placed stone at {}", coordinates(pos)),
+            None => self.log_pass(player),
+        }
+    }
+
+    fn error_feedback(&mut self) {
+        if !self.muted {
+            self.feedback.error();
+        }
+    }
+
+    fn log_pass(&self, player: Player) {
+        log::info!("{player}: pass turn");
+        if self.game.is_scoring() {
+            log::info!("Both players passed. Mark dead groups with D and press Enter to count.");
+        }
+    }
+
     pub fn draw(&self, frame: &mut Frame) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
@@ -93,3 +179,52 @@ impl App {
         );
     }
 }
+
+/// Coordinates the way Go players write them, e.g. `D4` with A1 in the bottom left corner
+fn coordinates(pos: Pos<BoardSize9x9>) -> String {
+    pos.display_with(Origin::BottomLeft, Notation::Letters)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+    use pretty_assertions::assert_eq;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Counts how often the user was told about an error
+    struct CountingFeedback(Rc<Cell<usize>>);
+
+    impl Feedback for CountingFeedback {
+        fn error(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        app.on_event(Event::Key(KeyEvent::from(key)));
+    }
+
+    #[test]
+    fn mute_suppresses_feedback() {
+        let num_errors = Rc::new(Cell::new(0));
+        let mut app = App::with_feedback(Box::new(CountingFeedback(Rc::clone(&num_errors))));
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(0, num_errors.get());
+
+        // The cursor is still on the stone that was just placed
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(1, num_errors.get());
+
+        press(&mut app, KeyCode::Char('m'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(1, num_errors.get());
+
+        press(&mut app, KeyCode::Char('m'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(2, num_errors.get());
+    }
+}
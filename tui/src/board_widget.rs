@@ -1,4 +1,4 @@
-use go_game::{Board, BoardSize, Pos};
+use go_game::{Board, BoardSize, Pos, StoneStyle};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -13,6 +13,10 @@ where
 {
     pub board: &'a Board<BS>,
     pub current_pos: Pos<BS>,
+    /// Marked so players can see what their opponent just played
+    pub last_move: Option<Pos<BS>>,
+    pub dead_stones: &'a [Pos<BS>],
+    pub style: StoneStyle,
 }
 
 impl<'a, BS: BoardSize> Widget for &BoardWidget<'a, BS>
@@ -26,10 +30,10 @@ where
                     (0..<BS as BoardSize>::SIZE)
                         .map(|x| {
                             let is_current_pos = self.current_pos == Pos::from_xy(x, y);
+                            let is_dead = self.dead_stones.contains(&Pos::from_xy(x, y));
                             let cell = self.board[Pos::from_xy(x, y)];
                             let cell_str = match cell {
-                                Some(go_game::Player::White) => "○ ", // white stone
-                                Some(go_game::Player::Black) => "● ", // black stone
+                                Some(_) => format!("{} ", self.style.glyph(cell)),
                                 None => match (x, y) {
                                     (0, 0) => "┌─",                                     // top left corner
                                     (0, n) if n == <BS as BoardSize>::SIZE - 1 => "└─", // bottom left corner
@@ -44,14 +48,23 @@ where
                                     (_, 0) => "┬─",                                     // top edge
                                     (n, _) if n == <BS as BoardSize>::SIZE - 1 => "┤ ", // right edge
                                     (_, n) if n == <BS as BoardSize>::SIZE - 1 => "┴─", // bottom edge
+                                    (x, y) if Pos::<BS>::from_xy(x, y).is_star_point() => "•─", // star point (hoshi)
                                     (_, _) => "┼─", // middle cell
-                                },
+                                }
+                                .to_string(),
+                            };
+                            let cell_str = if is_dead {
+                                cell_str.dim().crossed_out()
+                            } else if self.last_move == Some(Pos::from_xy(x, y)) {
+                                cell_str.red().bold()
+                            } else {
+                                cell_str.into()
                             };
                             if is_current_pos {
                                 // TODO Only highlight the first character
                                 cell_str.on_blue().bold()
                             } else {
-                                cell_str.into()
+                                cell_str
                             }
                         })
                         .collect::<Vec<_>>(),
@@ -61,3 +74,37 @@ where
         Paragraph::new(Text::from(text)).render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use go_game::{BoardSize9x9, Player};
+    use pretty_assertions::assert_eq;
+
+    fn render_row(board: &Board<BoardSize9x9>, y: u16) -> String {
+        let widget = BoardWidget {
+            board,
+            current_pos: Pos::from_xy(0, 0),
+            last_move: None,
+            dead_stones: &[],
+            style: StoneStyle::UNICODE,
+        };
+        let area = Rect::new(0, 0, 18, 9);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        (0..area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn empty_star_point() {
+        let board = Board::<BoardSize9x9>::new();
+        assert_eq!("├─┼─┼─┼─•─┼─┼─┼─┤ ", render_row(&board, 4));
+    }
+
+    #[test]
+    fn occupied_star_point() {
+        let mut board = Board::<BoardSize9x9>::new();
+        board.set(Pos::from_xy(4, 4), Some(Player::Black));
+        assert_eq!("├─┼─┼─┼─● ┼─┼─┼─┤ ", render_row(&board, 4));
+    }
+}
@@ -0,0 +1,18 @@
+use actually_beep::beep_with_hz_and_millis;
+
+/// How the app tells the user that something went wrong, e.g. an illegal move
+pub trait Feedback {
+    fn error(&mut self);
+}
+
+/// The default feedback: a short low beep
+pub struct Beep;
+
+impl Feedback for Beep {
+    fn error(&mut self) {
+        // Machines without audio can still play, they just don't hear the beep
+        if let Err(err) = beep_with_hz_and_millis(200, 75) {
+            log::warn!("Failed to beep: {err}");
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use go_game::{BoardSize, Game, Player, Pos};
+use go_game::{
+    BoardSize, Game, GamePhase, Outcome, OutcomeMargin, PlaceStoneError, Player, Pos, ScoringRules,
+    StoneStyle,
+};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -16,6 +19,12 @@ where
 {
     game: Game<BS>,
     current_pos: Pos<BS>,
+
+    /// Stones marked as dead after the game ended
+    dead_stones: Vec<Pos<BS>>,
+
+    /// Why the last move was rejected, shown in the status line until the next move or pass
+    rejection: Option<&'static str>,
 }
 
 impl<BS: BoardSize> GameWidget<BS>
@@ -27,6 +36,8 @@ where
         Self {
             game: Game::new(),
             current_pos: Pos::from_xy(0, 0),
+            dead_stones: Vec::new(),
+            rejection: None,
         }
     }
 
@@ -62,12 +73,88 @@ where
         }
     }
 
-    pub fn place_stone(&mut self) -> Result<(), go_game::PlaceStoneError> {
-        self.game.place_stone(self.current_pos)
+    pub fn move_to_left_edge(&mut self) {
+        self.current_pos = Pos::from_xy(0, self.current_pos.y());
+    }
+
+    pub fn move_to_right_edge(&mut self) {
+        self.current_pos = Pos::from_xy(BS::SIZE - 1, self.current_pos.y());
+    }
+
+    pub fn move_to_top_edge(&mut self) {
+        self.current_pos = Pos::from_xy(self.current_pos.x(), 0);
+    }
+
+    pub fn move_to_bottom_edge(&mut self) {
+        self.current_pos = Pos::from_xy(self.current_pos.x(), BS::SIZE - 1);
+    }
+
+    /// Jumps to the center point (tengen)
+    pub fn move_to_center(&mut self) {
+        self.current_pos = Pos::from_xy(BS::SIZE / 2, BS::SIZE / 2);
+    }
+
+    pub fn place_stone(&mut self) -> Result<(), PlaceStoneError> {
+        let result = self.game.place_stone(self.current_pos);
+        self.rejection = result.as_ref().err().map(rejection_text);
+        result
     }
 
     pub fn pass_turn(&mut self) {
         self.game.pass_turn();
+        self.rejection = None;
+    }
+
+    /// Lets the engine play for the current player. Returns where it placed a stone,
+    /// or `None` if it passed.
+    pub fn play_engine_move(&mut self) -> Option<Pos<BS>> {
+        match self.game.heuristic_move() {
+            Some(pos) => {
+                self.game
+                    .place_stone(pos)
+                    .expect("The engine only suggests legal moves");
+                self.rejection = None;
+                Some(pos)
+            }
+            None => {
+                self.pass_turn();
+                None
+            }
+        }
+    }
+
+    /// After both players passed, dead stones are marked before the final count
+    pub fn is_scoring(&self) -> bool {
+        self.game.phase() == GamePhase::Scoring
+    }
+
+    pub fn final_outcome(&self) -> Option<Outcome> {
+        match self.game.phase() {
+            GamePhase::Finished { outcome } => Some(outcome),
+            GamePhase::Playing | GamePhase::Scoring => None,
+        }
+    }
+
+    /// Marks the group at the cursor as dead, or as alive again if it already was marked dead
+    pub fn toggle_dead_group(&mut self) {
+        let group = self.game.group_stones_at(self.current_pos);
+        if self.dead_stones.contains(&self.current_pos) {
+            self.dead_stones.retain(|pos| !group.contains(pos));
+        } else {
+            self.dead_stones.extend(group);
+        }
+    }
+
+    pub fn finish_scoring(&mut self) -> Outcome {
+        self.game
+            .finish_scoring(&self.dead_stones, ScoringRules::Area)
+            .expect("Can only finish scoring while scoring")
+    }
+
+    fn current_outcome(&self) -> Outcome {
+        self.game
+            .score(&self.dead_stones)
+            .outcome(ScoringRules::Area, self.game.komi())
     }
 }
 
@@ -78,12 +165,25 @@ where
 {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let title = Line::from(" Go Board ".bold());
-        let instructions = Line::from(vec![
-            "Use arrow keys to move, ".into(),
-            "Enter or Space to place stone, ".into(),
-            "P to pass turn, ".into(),
-            "Esc or Q to quit.".into(),
-        ]);
+        let instructions = if self.is_scoring() {
+            Line::from(vec![
+                "Use arrow keys to move, ".into(),
+                "Home/End/PgUp/PgDn/C to jump, ".into(),
+                "D to mark a group as dead, ".into(),
+                "Enter to finish counting, ".into(),
+                "Esc or Q to quit.".into(),
+            ])
+        } else {
+            Line::from(vec![
+                "Use arrow keys to move, ".into(),
+                "Home/End/PgUp/PgDn/C to jump, ".into(),
+                "Enter or Space to place stone, ".into(),
+                "P to pass turn, ".into(),
+                "A to toggle computer opponent, ".into(),
+                "M to mute, ".into(),
+                "Esc or Q to quit.".into(),
+            ])
+        };
         let block = Block::bordered()
             .title(title)
             .title_bottom(instructions)
@@ -93,30 +193,60 @@ where
         let board = BoardWidget {
             board: self.game.board(),
             current_pos: self.current_pos,
+            last_move: self.game.last_move(),
+            dead_stones: &self.dead_stones,
+            style: StoneStyle::default(),
         };
         board.render(inner_area, buf);
-        let player_text = Text::from(vec![
+        let status_line = if let Some(outcome) = self.final_outcome() {
             Line::from(vec![
-                "Turn: ".into(),
+                "Final result: ".into(),
+                outcome_text(outcome).yellow(),
+            ])
+        } else if self.is_scoring() {
+            Line::from(vec![
+                "Counting: ".into(),
+                outcome_text(self.current_outcome()).yellow(),
+            ])
+        } else {
+            let mut line = Line::from(vec![
+                "Move ".into(),
+                (self.game.move_count() + 1).to_string().yellow(),
+                " | Turn: ".into(),
                 player_name(self.game.current_player()).yellow(),
-            ]),
+            ]);
+            if let Some(rejection) = self.rejection {
+                line.push_span(" | Illegal move: ");
+                line.push_span(rejection.red());
+            }
+            line
+        };
+        let mut player_text = Text::from(vec![
+            status_line,
             Line::from(vec![
                 "Prisoners Captured: ".into(),
+                self.game.captures_summary().yellow(),
+            ]),
+        ]);
+        if self.game.is_game_over() {
+            let score = self.game.score(&self.dead_stones);
+            player_text.push_line(Line::from(vec![
+                "Territory: ".into(),
                 format!(
                     "{}: {}",
                     player_name(Player::Black),
-                    self.game.num_captured_by(Player::Black).into_usize()
+                    score.territory(Player::Black).into_usize()
                 )
                 .yellow(),
                 " | ".into(),
                 format!(
                     "{}: {}",
                     player_name(Player::White),
-                    self.game.num_captured_by(Player::White).into_usize()
+                    score.territory(Player::White).into_usize()
                 )
                 .yellow(),
-            ]),
-        ]);
+            ]));
+        }
         Paragraph::new(player_text)
             .centered()
             .block(block)
@@ -130,3 +260,95 @@ fn player_name(player: Player) -> &'static str {
         Player::White => "White",
     }
 }
+
+/// Short reason for the status line, the log has the full error message
+fn rejection_text(error: &PlaceStoneError) -> &'static str {
+    match error {
+        PlaceStoneError::CellOccupied => "Occupied",
+        PlaceStoneError::Suicide => "Suicide",
+        PlaceStoneError::Ko => "Ko",
+        PlaceStoneError::Superko => "Superko",
+        PlaceStoneError::GameOver => "Game over",
+        PlaceStoneError::OutOfBounds { .. } => "Outside of the board",
+    }
+}
+
+pub fn outcome_text(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::WithWinner {
+            winner,
+            margin: OutcomeMargin::ByPoints { points_times_two },
+        } => format!(
+            "{} wins by {} points",
+            player_name(winner),
+            points_times_two as f32 / 2.0
+        ),
+        Outcome::WithWinner { winner, margin } => {
+            format!("{} wins ({:?})", player_name(winner), margin)
+        }
+        Outcome::Draw => "Draw".to_string(),
+        Outcome::Void => "Void".to_string(),
+        Outcome::Unfinished => "Unfinished".to_string(),
+        Outcome::Unknown => "Unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use go_game::BoardSize9x9;
+
+    fn render(widget: &GameWidget<BoardSize9x9>) -> String {
+        let area = Rect::new(0, 0, 120, 30);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        buf.content.iter().map(|cell| cell.symbol()).collect()
+    }
+
+    fn shows_rejection(widget: &GameWidget<BoardSize9x9>) -> bool {
+        render(widget).contains("Illegal move: Occupied")
+    }
+
+    fn widget_with_rejection() -> GameWidget<BoardSize9x9> {
+        let mut widget = GameWidget::new();
+        widget.place_stone().unwrap();
+        assert!(!shows_rejection(&widget));
+        assert!(matches!(
+            widget.place_stone(),
+            Err(PlaceStoneError::CellOccupied)
+        ));
+        assert!(shows_rejection(&widget));
+        widget
+    }
+
+    #[test]
+    fn rejection_stays_when_moving_the_cursor() {
+        let mut widget = widget_with_rejection();
+        widget.move_right();
+        assert!(shows_rejection(&widget));
+        widget.move_down();
+        assert!(shows_rejection(&widget));
+    }
+
+    #[test]
+    fn rejection_is_cleared_by_a_successful_move() {
+        let mut widget = widget_with_rejection();
+        widget.move_right();
+        widget.place_stone().unwrap();
+        assert!(!shows_rejection(&widget));
+    }
+
+    #[test]
+    fn rejection_is_cleared_by_passing() {
+        let mut widget = widget_with_rejection();
+        widget.pass_turn();
+        assert!(!shows_rejection(&widget));
+    }
+
+    #[test]
+    fn rejection_is_cleared_by_an_engine_move() {
+        let mut widget = widget_with_rejection();
+        assert!(widget.play_engine_move().is_some());
+        assert!(!shows_rejection(&widget));
+    }
+}
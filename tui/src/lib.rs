@@ -2,5 +2,7 @@
 
 mod app;
 mod board_widget;
+mod feedback;
 mod game_widget;
 pub use app::App;
+pub use feedback::{Beep, Feedback};